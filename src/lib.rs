@@ -9,9 +9,41 @@
 //! 
 //! Note here that to do a naive matrix calculation requires to hold the matrix in memory
 //! and then perform some sort of SGD based solution
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::default::Default;
-use std::hash::Hash;
+use std::fmt::{Debug, Display};
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// threshold used by calculate when none is given
+const DEFAULT_CONVERGENCE: f64 = 0.01;
+
+/// laziness auto stabilize switches on once the residual oscillates, the
+/// walk then stays put one step in ten
+const AUTO_LAZINESS: f64 = 0.9;
+
+/// per step score change under which a tracked node counts as settled
+const STABILIZATION_EPSILON: f64 = 1e-6;
+
+/// everything we know about a single edge besides its endpoints
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EdgeMeta {
+    weight: f64,
+    relation: Option<usize>,
+    timestamp: Option<u64>,
+}
+
+impl Default for EdgeMeta {
+    fn default() -> Self {
+        EdgeMeta {
+            weight: 1f64,
+            relation: None,
+            timestamp: None,
+        }
+    }
+}
 
 #[derive(Clone)]
 struct GraphNode<T>
@@ -20,8 +52,200 @@ where // creating bounds for this struct
 {
     node: T,
     incoming_edges: Vec<usize>,
-    outgoing_edges: usize,
+    incoming_meta: Vec<EdgeMeta>, // parallel to incoming_edges
+    // targets of every out edge, the out-degree is its length. this costs
+    // another usize per edge on top of incoming_edges but makes forward
+    // traversal possible without scanning the whole graph
+    outgoing_edges: Vec<usize>,
     score: f64,
+    pinned: bool,
+    weight: f64, // intrinsic importance, scales the teleport share
+    dangling_sink: bool, // collects rank from nodes without out edges
+    component: Option<usize>, // weak component as of calculate_per_component
+    stabilized_at: Option<u32>, // step its score settled in, when tracked
+    damping: Option<f64>, // overrides the graph's damping for this node
+    absorbing: bool, // holds on to its rank instead of following out edges
+    teleport_excluded: bool, // never a teleport destination
+}
+
+impl<T> GraphNode<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// a node with no edges yet
+    fn new(node: T, score: f64) -> GraphNode<T> {
+        GraphNode::<T> {
+            node,
+            incoming_edges: Vec::new(),
+            incoming_meta: Vec::new(),
+            outgoing_edges: Vec::new(),
+            score,
+            pinned: false,
+            weight: 1f64,
+            dangling_sink: false,
+            component: None,
+            stabilized_at: None,
+            damping: None,
+            absorbing: false,
+            teleport_excluded: false,
+        }
+    }
+
+    /// copy of this node with all its edges stripped
+    fn detached(&self) -> GraphNode<T> {
+        GraphNode::<T> {
+            incoming_edges: Vec::new(),
+            incoming_meta: Vec::new(),
+            outgoing_edges: Vec::new(),
+            node: self.node.clone(),
+            ..*self
+        }
+    }
+}
+
+/// How calculate_step updates the scores
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum IterationMode {
+    /// every update reads the previous step's scores (the default)
+    #[default]
+    Jacobi,
+    /// update in place, so nodes later in the sweep already see the new
+    /// scores of earlier nodes. usually converges in fewer iterations but the
+    /// result of each step depends on node insertion order
+    GaussSeidel,
+}
+
+/// How the last calculate run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// the residual dropped below the threshold
+    Converged,
+    /// the residual got stuck on a plateau above the threshold
+    Stalled,
+    /// the iteration cap was hit while still making progress
+    Truncated,
+}
+
+/// Something that can replay a graph's edges on demand, so the graph never
+/// has to fit in memory
+pub trait EdgeSource<T> {
+    /// every edge as (source, target). this gets called once per pass and
+    /// has to yield the same edges every time
+    fn edges(&self) -> impl Iterator<Item = (T, T)>;
+}
+
+/// Something that wants to hear about every step of a calculation, see
+/// Pagerank::calculate_with_reporter
+pub trait ProgressReporter {
+    /// called after every step with its number (counting from 1) and
+    /// residual
+    fn on_iteration(&mut self, iter: i32, residual: f64);
+}
+
+/// Edge changes that turn one graph into another, see Pagerank::diff
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphPatch<T> {
+    /// edges to add, repeated once per copy for parallel edges
+    pub added: Vec<(T, T)>,
+    /// edges to remove, repeated once per copy for parallel edges
+    pub removed: Vec<(T, T)>,
+}
+
+impl<T> GraphPatch<T> {
+    /// Does the patch change nothing?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Everything about one node in a single row, see Pagerank::node_records
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeRecord<T> {
+    /// the node itself
+    pub node: T,
+    /// its current score
+    pub score: f64,
+    /// number of incoming edges, parallel edges counted once per copy
+    pub in_degree: usize,
+    /// number of outgoing edges, parallel edges counted once per copy
+    pub out_degree: usize,
+}
+
+/// What to do once the graph hits its node cap, see set_eviction_policy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// never drop anything, the cap is ignored (the default)
+    #[default]
+    Never,
+    /// drop the nodes with the fewest edges (in plus out) to make room,
+    /// oldest first on ties
+    LowestDegree,
+}
+
+/// Where the rank of nodes without out edges goes when no dangling sinks
+/// are set, see set_dangling_handling
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DanglingHandling {
+    /// it leaks out of the graph and the scores sum to less than n (the
+    /// default)
+    #[default]
+    Leak,
+    /// it gets spread over the graph like a teleport, following the
+    /// personalization when one is set. scores keep summing to n
+    Teleport,
+}
+
+/// Which way to follow edges from a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// along out edges, to the targets
+    Outgoing,
+    /// against in edges, to the sources
+    Incoming,
+}
+
+/// Where a node sits in the bow-tie picture of a directed graph, see
+/// Pagerank::bowtie_classification
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BowtieComponent {
+    /// the largest strongly connected component
+    Core,
+    /// can reach the core but isn't reachable from it
+    In,
+    /// reachable from the core but can't reach it
+    Out,
+    /// hangs off In (reachable from it) or off Out (reaches it) without
+    /// touching the core
+    Tendril,
+    /// reachable from In and reaches Out while bypassing the core
+    Tube,
+    /// not connected to any of the above
+    Disconnected,
+}
+
+/// hasher behind node_positions, randomly seeded unless the graph was made
+/// with new_deterministic
+#[derive(Clone)]
+enum NodeHasher {
+    Random(RandomState),
+    Fixed,
+}
+
+impl Default for NodeHasher {
+    fn default() -> Self {
+        NodeHasher::Random(RandomState::new())
+    }
+}
+
+impl BuildHasher for NodeHasher {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        match self {
+            NodeHasher::Random(state) => state.build_hasher(),
+            NodeHasher::Fixed => DefaultHasher::new(), // same keys every run
+        }
+    }
 }
 
 /// Pagerank bby
@@ -33,8 +257,31 @@ where
     damping: f64,
     nodes: Vec<GraphNode<T>>,
     edges: usize,
-    node_positions: HashMap<T, usize>,
-    nodes_with_incoming: Option<usize>
+    node_positions: HashMap<T, usize, NodeHasher>,
+    nodes_with_incoming: Option<usize>,
+    max_iterations: i32,
+    last_outcome: Option<RunOutcome>,
+    stall_epsilon: f64,
+    stall_window: usize,
+    iteration_mode: IterationMode,
+    personalization: Option<Vec<f64>>,
+    last_residual: Option<f64>,
+    restart_every: Option<u32>,
+    steps_taken: u64,
+    relations: HashMap<String, usize>,
+    relation_weights: Vec<f64>,
+    named_scores: HashMap<String, Vec<f64>>,
+    eviction: EvictionPolicy,
+    max_nodes: usize,
+    sort_epsilon: f64,
+    dangling_handling: DanglingHandling,
+    track_stabilization: bool,
+    max_incoming: usize,
+    snapshot_iterations: Vec<u32>,
+    snapshots: HashMap<u32, Vec<f64>>,
+    scores_deferred: bool, // degree only until the first calculate
+    laziness: f64,
+    auto_stabilize: bool,
 }
 
 impl<T> Pagerank<T>
@@ -47,12 +294,94 @@ where
             damping: 0.85, // magic number for the random surfer
             nodes: Vec::new(),
             edges: 0,
-            node_positions: HashMap::<T, usize>::new(),
+            node_positions: HashMap::default(),
             nodes_with_incoming: None,
+            max_iterations: 1000, // plenty for any sane graph
+            last_outcome: None,
+            stall_epsilon: 1e-9,
+            stall_window: 5,
+            iteration_mode: IterationMode::Jacobi,
+            personalization: None,
+            last_residual: None,
+            restart_every: None,
+            steps_taken: 0,
+            relations: HashMap::new(),
+            relation_weights: Vec::new(),
+            named_scores: HashMap::new(),
+            eviction: EvictionPolicy::Never,
+            max_nodes: usize::MAX,
+            sort_epsilon: 0f64,
+            dangling_handling: DanglingHandling::Leak,
+            track_stabilization: false,
+            max_incoming: usize::MAX,
+            snapshot_iterations: Vec::new(),
+            snapshots: HashMap::new(),
+            scores_deferred: false,
+            laziness: 1f64,
+            auto_stabilize: false,
         }
     }
     
+    /// A graph that only keeps degree bookkeeping until it's first
+    /// calculated, for when all you want is a degree counting sketch
+    ///
+    /// every score reads 0.0 until then. the first calculate (or step) puts
+    /// every unpinned node on the 1 - damping baseline and carries on as usual
+    pub fn new_degree_only() -> Pagerank<T> {
+        Pagerank::<T> {
+            scores_deferred: true,
+            ..Pagerank::new()
+        }
+    }
+
+    /// A graph whose node lookup table hashes the same way in every process,
+    /// for reproducible runs and golden file tests
+    ///
+    /// ids already follow insertion order, this pins down the hash table too.
+    /// the hash is only fixed for a given build of std
+    pub fn new_deterministic() -> Pagerank<T> {
+        Pagerank::<T> {
+            node_positions: HashMap::with_hasher(NodeHasher::Fixed),
+            ..Pagerank::new()
+        }
+    }
+
+    /// Build a graph from a closure handing out one edge per call until it
+    /// returns None, handy for generated graphs
+    pub fn from_edge_fn<F: FnMut() -> Option<(T, T)>>(f: F) -> Pagerank<T> {
+        std::iter::from_fn(f).collect()
+    }
+
+    /// Build the graph twice, once in the given edge order and once shuffled,
+    /// and panic unless every node ends up with the same score (within 1e-9)
+    ///
+    /// meant for tests guarding against float summation order sneaking into
+    /// the results. the shuffle uses a fixed seed so a failure reproduces
+    pub fn assert_deterministic(edges: &[(T, T)], convergence: f64)
+    where
+        T: Debug,
+    {
+        let order = SplitMix64(0x5EED).sample(edges.len(), edges.len());
+        let mut first = edges.iter().cloned().collect::<Pagerank<T>>();
+        let mut second = order.into_iter().map(|i| edges[i].clone()).collect::<Pagerank<T>>();
+        first.calculate_with_convergence(convergence);
+        second.calculate_with_convergence(convergence);
+
+        assert_eq!(first.len(), second.len(), "node counts differ");
+        for n in first.nodes.iter() {
+            let other = second.get_score(n.node.clone()).unwrap();
+            assert!(
+                (n.score - other).abs() <= 1e-9,
+                "{:?} scored {} and {} across builds",
+                n.node,
+                n.score,
+                other
+            );
+        }
+    }
+
     /// setter for the damping factor
+    /// nodes still sitting on the old default score move to the new one
     pub fn set_damping_factor(
         &mut self,
         factor: u8,
@@ -61,266 +390,5360 @@ where
             return Err("{val} needs to be bellow 100".to_string());
         }
 
-        self.damping = factor as f64 / 100_f64;
+        self.rebase_damping(factor as f64 / 100_f64);
         Ok(())
     }
 
-    /// BASIC GRAPH STUFF
-    
-    // Get or create a node
-    pub fn get_or_create_node(&mut self, node: T) -> usize {
-        match self.node_positions.get(&node) { 
-            Some(&value) => value,
-            _ => { // if the node doesn't exist, make it
-                let id = self.nodes.len();
-                self.nodes.push(GraphNode::<T>{
-                    node: node.clone(),
-                    incoming_edges: Vec::new(),
-                    outgoing_edges: 0,
-                    score: 1f64 - self.damping
-                });
-                self.node_positions.insert(node, id);
-                self.nodes_with_incoming = None; // new nodes have no edges 
-                id // return id
-            }
+    /// setter for the damping factor as a plain f64 in [0, 1)
+    /// nodes still sitting on the old default score move to the new one
+    pub fn set_damping(&mut self, damping: f64) -> Result<(), String> {
+        if !(0f64..1f64).contains(&damping) {
+            return Err(format!("{damping} needs to be in [0, 1)"));
         }
+
+        self.rebase_damping(damping);
+        Ok(())
     }
 
-    /// adding nodes to the graph. 
-    pub fn add_edge(&mut self, source: T, target: T) {
-        let source = self.get_or_create_node(source);
-        let target = self.get_or_create_node(target);
-        // this is a directed graph
-        self.nodes[source].outgoing_edges += 1;
-        self.nodes[target].incoming_edges.push(source);
-        self.edges +=1;
+    /// Override the damping factor for one node, in [0, 1)
+    ///
+    /// the node's update becomes (1 - d) * teleport + d * incoming with its
+    /// own d, so e.g. hubs can be made to teleport less. everyone else keeps
+    /// the global value. local_rank's push estimate sticks to the global
+    /// damping. creates the node if needed
+    pub fn set_node_damping(&mut self, node: T, damping: f64) -> Result<(), String> {
+        if !(0f64..1f64).contains(&damping) {
+            return Err(format!("{damping} needs to be in [0, 1)"));
+        }
+
+        let id = self.get_or_create_node(node);
+        self.nodes[id].damping = Some(damping);
+        Ok(())
     }
 
-    /// Get node score
-    pub fn get_score(&self, node: T) -> Option<f64> {
-        self.node_positions
-            .get(&node)
-            .map(|id| self.nodes[*id].score)
+    /// swap the damping factor, keeping untouched nodes on the baseline
+    fn rebase_damping(&mut self, damping: f64) {
+        let (old_baseline, baseline) = (1f64 - self.damping, 1f64 - damping);
+        for n in self.nodes.iter_mut() {
+            if !n.pinned && n.score == old_baseline {
+                n.score = baseline;
+            }
+        }
+        self.damping = damping;
     }
 
-    /// Get node's number of incoming edges
-    pub fn get_incoming_edges(&self, node: T) -> Option<usize> {
-        self.node_positions
-            .get(&node)
-            .map(|id| self.nodes[*id].incoming_edges.len())
+    /// Put every unpinned node back on the default 1 - damping score
+    pub fn reset_scores(&mut self) {
+        let baseline = 1f64 - self.damping;
+        for n in self.nodes.iter_mut().filter(|n| !n.pinned) {
+            n.score = baseline;
+        }
     }
 
-    /// Get nodes number of outgoing edges
-    pub fn get_outgoing_edges(&self, node: T) -> Option<usize> {
-        self.node_positions
-            .get(&node)
-            .map(|id| self.nodes[*id].outgoing_edges)
+    /// Seed the scores from an earlier version of the graph, matching nodes
+    /// by value
+    ///
+    /// nodes previous doesn't have go back to the baseline and nodes only in
+    /// previous are ignored. pinned nodes keep their score. calculate carries
+    /// on from whatever scores are there, so the next run starts warm
+    pub fn warm_start_from(&mut self, previous: &Pagerank<T>) {
+        self.ensure_scores();
+        let baseline = 1f64 - self.damping;
+        for n in self.nodes.iter_mut().filter(|n| !n.pinned) {
+            n.score = previous
+                .node_positions
+                .get(&n.node)
+                .map_or(baseline, |id| previous.nodes[*id].score);
+        }
     }
 
-    /// len of all edges
-    pub fn len_nodes_with_incoming_edges(&mut self) -> usize {
-        if let Some(n) = self.nodes_with_incoming {
-            return n;
+    /// Run every score (pinned ones included) through f in place, e.g. to
+    /// log scale them for display. a later calculate carries on from the
+    /// transformed scores. if f turns any score into NaN or an infinity
+    /// nothing changes and it's an error, the ranking can't order those
+    pub fn map_scores<F: Fn(f64) -> f64>(&mut self, f: F) -> Result<(), String> {
+        let mapped = self.nodes.iter().map(|n| f(n.score)).collect::<Vec<f64>>();
+        if let Some((n, score)) = self.nodes.iter().zip(mapped.iter()).find(|(_, score)| !score.is_finite()) {
+            return Err(format!("{} got mapped to {score}", n.score));
         }
 
-        let mut total = 0 as usize;
-        for node in self.nodes.iter() {
-            if node.incoming_edges.len() > 0 {
-                total += 1;
-            }
+        for (n, score) in self.nodes.iter_mut().zip(mapped) {
+            n.score = score;
         }
+        Ok(())
+    }
 
-        self.nodes_with_incoming = Some(total);
+    /// getter for the damping factor
+    pub fn damping(&self) -> f64 {
+        self.damping
+    }
 
-        total
+    /// getter for the damping factor as a rounded percentage, the same units
+    /// set_damping_factor takes
+    pub fn damping_percent(&self) -> u8 {
+        (self.damping * 100_f64).round() as u8
     }
 
-    /// 
-    pub fn calculate_step(&mut self) -> f64 {
-        let mut current_iter = self.nodes.clone();
+    /// Suggest the largest damping expected to converge within target_iters
+    ///
+    /// the error shrinks by roughly a factor of damping per step, so
+    /// iterations ~ ln(convergence) / ln(damping) and damping ~
+    /// convergence^(1 / target_iters). only an estimate, the graph's
+    /// structure is ignored. always a value set_damping accepts, target_iters
+    /// has to be above 0 and convergence positive
+    pub fn damping_for_iterations(&self, target_iters: u32, convergence: f64) -> Result<f64, String> {
+        if target_iters == 0 {
+            return Err(format!("{target_iters} needs to be above 0"));
+        }
+        if convergence.is_nan() || convergence <= 0f64 {
+            return Err(format!("{convergence} needs to be above 0"));
+        }
 
-        let nodes = &self.nodes;
+        Ok(convergence.powf(1f64 / target_iters as f64).clamp(0f64, 1f64 - f64::EPSILON))
+    }
 
-        self.nodes
-            .iter()
-            .enumerate()
-            .map(|(id, n)| {
-                // define a closure over the nodes 
-                // god fp is rad
-                let score = n
-                    .incoming_edges
-                    .iter()
-                    .map(|node| {
-                        nodes[*node].score / nodes[*node].outgoing_edges as f64
-                    })
-                    .sum::<f64>();
-                    // 
-                    current_iter[id].score = (1f64 - self.damping) + (self.damping * score);
-            })
-            .for_each(drop); // cleanup
+    /// setter for the iteration cap used by the calculate family
+    pub fn set_max_iterations(&mut self, max: i32) -> Result<(), String> {
+        if max <= 0 {
+            return Err(format!("{max} needs to be above 0"));
+        }
+
+        self.max_iterations = max;
+        Ok(())
+    }
 
-        let convergence: f64 = self
+    /// setter for the iteration mode, see IterationMode
+    pub fn set_iteration_mode(&mut self, mode: IterationMode) {
+        self.iteration_mode = mode;
+    }
+
+    /// Force a full teleport every k-th step instead of only teleporting
+    /// with probability 1 - damping
+    ///
+    /// on every k-th call to calculate_step all (unpinned) nodes get reset to
+    /// their teleport share, the steps in between follow the usual update.
+    /// this is a different dynamical system from plain pagerank and the big
+    /// jump on restart steps shows up in the residual. None goes back to the
+    /// standard walk. the step count starts over whenever this is set
+    pub fn set_restart_every(&mut self, k: Option<u32>) -> Result<(), String> {
+        if k == Some(0) {
+            return Err("restart interval needs to be above 0".to_string());
+        }
+
+        self.restart_every = k;
+        self.steps_taken = 0;
+        Ok(())
+    }
+
+    /// Personalize the teleport step with a weight computed from each node
+    ///
+    /// weights are evaluated once for the current nodes and normalized over
+    /// the whole graph, so nodes created afterwards get no teleport mass
+    pub fn set_personalization_fn<F: Fn(&T) -> f64>(&mut self, f: F) -> Result<(), String> {
+        let weights = self
             .nodes
             .iter()
-            .enumerate()
-            .map(|(id, n) | {
-                let diff = n.score - current_iter[id].score;
-                diff * diff
-            })
-            .sum();
+            .map(|n| f(&n.node))
+            .collect::<Vec<f64>>();
 
-        self.nodes = current_iter;
-        convergence.sqrt() / self.len_nodes_with_incoming_edges() as f64
-    }
+        if weights.iter().any(|w| !w.is_finite() || *w < 0f64) {
+            return Err("personalization weights need to be finite and non-negative".to_string());
+        }
+        if weights.iter().sum::<f64>() <= 0f64 {
+            return Err("personalization weights can't all be zero".to_string());
+        }
 
-    /// calculate pagerank with custom convergence
-    pub fn calculate_with_convergence(&mut self, convergence: f64) -> i32 {
-        let mut iterations = 0;
+        self.personalization = Some(weights);
+        Ok(())
+    }
 
-        loop {
-            if self.calculate_step() < convergence {
-                break;
-            }
-            iterations += 1;
+    /// Give a node intrinsic importance that biases the teleport toward it
+    ///
+    /// weights multiply into the teleport distribution (on top of any
+    /// personalization) and get renormalized over the graph, so a node with
+    /// weight 2 gets twice the random jumps of a default 1.0 node. unlike
+    /// personalization this sticks to the node. creates the node if needed
+    pub fn set_node_weight(&mut self, node: T, weight: f64) -> Result<(), String> {
+        if !weight.is_finite() || weight < 0f64 {
+            return Err(format!("{weight} needs to be finite and non-negative"));
         }
-        iterations
+
+        let id = self.get_or_create_node(node);
+        self.nodes[id].weight = weight;
+        Ok(())
     }
 
-    /// Calulate pagerank with predefined covergence
-    pub fn calculate(&mut self) -> i32 {
-        self.calculate_with_convergence(0.01)
+    /// Send the rank of nodes without out edges to a chosen set of sinks
+    ///
+    /// each step the summed score of every dangling node gets split evenly
+    /// between the sinks instead of leaking away. sinks that aren't in the
+    /// graph yet get added, and an empty vec goes back to leaking
+    pub fn set_dangling_sink(&mut self, nodes: Vec<T>) {
+        self.set_flag(nodes, |n| &mut n.dangling_sink);
     }
-    
-    /// Get count of nodes in graph
-    pub fn len(&self) -> usize {
-        self.nodes.len()
+
+    /// Keep teleporting surfers from ever landing on these nodes
+    ///
+    /// the teleport gets spread over everyone else instead (on top of any
+    /// personalization), excluded nodes only get rank through their incoming
+    /// edges. each call's list takes over from the last one
+    pub fn set_teleport_excluded(&mut self, nodes: Vec<T>) {
+        self.set_flag(nodes, |n| &mut n.teleport_excluded);
     }
 
-    /// Return nodes sorted by pagerank
-    pub fn nodes(&self) -> Vec<(&T, f64)> {
-        let mut nodes = self
-            .nodes
-            .iter()
-            .map(|node| (&node.node, node.score))
-            .collect::<Vec<(&T, f64)>>();
-        
-        nodes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    /// Turn nodes into traps that keep whatever rank reaches them
+    ///
+    /// an absorbing node's out edges stop carrying score and its own score
+    /// stays with it as if it linked only to itself, so only teleporting gets
+    /// rank back out. nodes left off a later call stop absorbing
+    pub fn set_absorbing(&mut self, nodes: Vec<T>) {
+        self.set_flag(nodes, |n| &mut n.absorbing);
+    }
 
-        nodes
+    /// clear a per-node flag everywhere, then raise it on nodes (creating
+    /// the ones that don't exist)
+    fn set_flag<F: Fn(&mut GraphNode<T>) -> &mut bool>(&mut self, nodes: Vec<T>, flag: F) {
+        for n in self.nodes.iter_mut() {
+            *flag(n) = false;
+        }
+        for node in nodes {
+            let id = self.get_or_create_node(node);
+            *flag(&mut self.nodes[id]) = true;
+        }
     }
 
-    /// Get count of edges in graph
-    pub fn len_node(&self) -> usize {
-        self.edges
+    /// Choose what happens to the rank of dangling nodes when there are no
+    /// dangling sinks
+    ///
+    /// with Teleport each step computes
+    /// score_i = (1-d)*t_i + d*(links_i + dangling*t_i/n) where t is the
+    /// teleport vector (summing to n). dividing through by n gives the
+    /// canonical personalized pagerank with p = t/n, so normalized scores
+    /// form a distribution
+    pub fn set_dangling_handling(&mut self, handling: DanglingHandling) {
+        self.dangling_handling = handling;
     }
 
-    /// Is the graph empty?
-    pub fn is_empty(&self) -> bool {
-        self.nodes.is_empty()
+    /// Cap the node count, evicting nodes according to policy when a new
+    /// node would go over it
+    ///
+    /// evicted nodes lose all their edges and every remaining id gets packed
+    /// down, so hold on to values rather than ids. the endpoints of the edge
+    /// being added are never evicted for it. every way of creating a node
+    /// counts (register_nodes, pins, per-node settings and so on) except
+    /// calculate_streaming and calculate_over, which need a score for every
+    /// node they see. cap has to be at least 2
+    pub fn set_eviction_policy(&mut self, policy: EvictionPolicy, cap: usize) -> Result<(), String> {
+        if cap < 2 {
+            return Err(format!("{cap} needs to be at least 2"));
+        }
+
+        self.eviction = policy;
+        self.max_nodes = cap;
+        Ok(())
     }
 
-}
+    /// Make the walk lazy, taking the usual step with probability p and
+    /// staying put otherwise
+    ///
+    /// every step becomes score = (1 - p) * old + p * update, which has the
+    /// same fixed point but can't oscillate on bipartite or otherwise
+    /// periodic graphs. p has to be in (0, 1], 1 (the default) is the plain
+    /// update and 0.5 the classic lazy walk
+    pub fn set_laziness(&mut self, p: f64) -> Result<(), String> {
+        if p.is_nan() || p <= 0f64 || p > 1f64 {
+            return Err(format!("{p} needs to be in (0, 1]"));
+        }
 
-impl<T> Default for Pagerank<T>
-where
-    T: Eq + Hash + Clone 
-{
-    fn default() -> Self {
-        Self::new()
-    }    
-}
+        self.laziness = p;
+        Ok(())
+    }
 
+    /// getter for the laziness, auto stabilize may have switched it on
+    pub fn laziness(&self) -> f64 {
+        self.laziness
+    }
 
-#[cfg(test)]
-mod tests {
-    /// Yeah im aware i need more test coverage
-    use std::ops::Add;
+    /// Watch the residual in calculate_with_convergence and make the walk a
+    /// little lazy if it starts oscillating
+    ///
+    /// once a couple of steps in a row undo the one before, the rest of the run
+    /// steps with a laziness of 0.9 (staying put one step in ten), which then
+    /// sticks for later runs. does nothing when a laziness is already set
+    pub fn set_auto_stabilize(&mut self, enabled: bool) {
+        self.auto_stabilize = enabled;
+    }
 
-    use crate::Pagerank;
+    /// Only let the first cap incoming edges of each node (in insertion
+    /// order) count when calculating
+    ///
+    /// meant to blunt link farms. the edges stay stored and still count
+    /// towards their source's out-degree, so the share they would have
+    /// carried just leaks away. usize::MAX (the default) lifts the cap.
+    /// calculate_streaming and calculate_over don't apply it
+    pub fn set_max_incoming_per_node(&mut self, cap: usize) {
+        self.max_incoming = cap;
+    }
 
-    #[test]
-    fn test_set_damping() {
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.set_damping_factor(22);
+    /// setter for the epsilon under which nodes() treats scores as tied
+    ///
+    /// tied nodes fall back to insertion order so tiny float noise can't
+    /// reshuffle them, nodes_sorted_by_value orders them by value instead
+    /// when the node type has an order. 0 (the default) only ties exactly
+    /// equal scores
+    pub fn set_sort_epsilon(&mut self, eps: f64) -> Result<(), String> {
+        if eps.is_nan() || eps < 0f64 {
+            return Err(format!("{eps} needs to be 0 or above"));
+        }
 
-        assert_eq!(0.22, pagerank.damping)
+        self.sort_epsilon = eps;
+        Ok(())
     }
 
-    #[test]
-    fn test_three_nodes_are_created() {
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "bbb");
-        pagerank.add_edge("bbb", "ccc");
-        assert_eq!(3, pagerank.len())
+    /// Record the step each node's score settled in during calculate, see
+    /// stabilized_at. costs an extra copy of the scores per step
+    pub fn set_track_stabilization(&mut self, track: bool) {
+        self.track_stabilization = track;
     }
 
-    //Edge case
-    #[test]
-    fn test_only_one_node_created() {
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "aaa");
-        assert_eq!(1, pagerank.len())
+    /// Step of the last tracked calculate after which the node's score
+    /// never again moved by more than 1e-6 per step
+    ///
+    /// steps count from 1 and include the final one. None when tracking is
+    /// off, the node doesn't exist or its score was still moving at the end
+    pub fn stabilized_at(&self, node: &T) -> Option<u32> {
+        self.node_positions.get(node).and_then(|id| self.nodes[*id].stabilized_at)
     }
 
-    #[test]
-    fn test_edges(){
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "bbb");
-        // gonna pack together multiple tests here
+    /// Copy the scores aside after these steps of every calculate, see
+    /// snapshot. steps count from 1, an empty vec stops capturing
+    pub fn set_snapshot_iterations(&mut self, iters: Vec<u32>) {
+        self.snapshot_iterations = iters;
+    }
 
-        // test one edge
-        assert_eq!(Some(1), pagerank.get_incoming_edges("bbb"));
-        assert_eq!(Some(0), pagerank.get_incoming_edges("aaa")); // aaa has no incoming edges
+    /// Scores captured after this step of the last calculate, in node
+    /// insertion order so two snapshots line up for diffing
+    ///
+    /// None if the step wasn't asked for or the run ended before it. removing
+    /// nodes throws every snapshot away
+    pub fn snapshot(&self, iter: u32) -> Option<Vec<(&T, f64)>> {
+        self.snapshots
+            .get(&iter)
+            .map(|scores| self.nodes.iter().zip(scores.iter()).map(|(n, score)| (&n.node, *score)).collect())
+    }
 
-        // test two edges
-        pagerank.add_edge("ccc", "bbb");
-        assert_eq!(Some(2), pagerank.get_incoming_edges("bbb"));
-        assert_eq!(Some(0), pagerank.get_outgoing_edges("bbb"))
+    /// Did the last calculate reach the convergence threshold?
+    /// Some(false) means it was stopped by the iteration cap, None means
+    /// nothing has been calculated yet
+    pub fn last_run_converged(&self) -> Option<bool> {
+        self.last_outcome.map(|outcome| outcome == RunOutcome::Converged)
     }
 
-    #[test]
-    fn test_score(){
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "bbb");
-        pagerank.add_edge("bbb", "ccc");
-        pagerank.add_edge("ddd", "aaa");
-        pagerank.add_edge("eee", "ddd");
+    /// How the last calculate ended, this tells a plateau apart from a run
+    /// that was cut off by the cap. None if nothing has been calculated yet
+    pub fn last_run_outcome(&self) -> Option<RunOutcome> {
+        self.last_outcome
+    }
 
-        // default score
-        assert_eq!(15_i64, (pagerank.get_score("aaa").expect("float") * 100_f64) as i64); 
+    /// setter for plateau detection
+    ///
+    /// a run is stalled once the residual changes by less than epsilon (relative
+    /// to the previous residual) for window steps in a row. a window of 0
+    /// turns detection off
+    pub fn set_stall_detection(&mut self, epsilon: f64, window: usize) -> Result<(), String> {
+        if epsilon.is_nan() || epsilon < 0f64 {
+            return Err(format!("{epsilon} needs to be 0 or above"));
+        }
 
-        // assert default score is shared across nodes
-        assert_eq!(pagerank.get_score("aaa"), pagerank.get_score("ddd")); 
+        self.stall_epsilon = epsilon;
+        self.stall_window = window;
+        Ok(())
     }
 
-    #[test]
-    fn test_iteration() {
-        let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "bbb");
-        pagerank.add_edge("bbb", "ccc");
-        pagerank.add_edge("ddd", "aaa");
+    /// BASIC GRAPH STUFF
+    
+    // Get or create a node
+    // a new node can evict another one when there's a node cap, so don't
+    // hold on to ids across calls
+    pub fn get_or_create_node(&mut self, node: T) -> usize {
+        if self.eviction == EvictionPolicy::LowestDegree
+            && self.nodes.len() >= self.max_nodes
+            && !self.node_positions.contains_key(&node)
+        {
+            self.evict_lowest_degree(self.nodes.len() + 1 - self.max_nodes, &[]);
+        }
+        self.get_or_push_node(node)
+    }
+
+    /// get_or_create_node without the node cap
+    fn get_or_push_node(&mut self, node: T) -> usize {
+        match self.node_positions.get(&node) { 
+            Some(&value) => value,
+            _ => { // if the node doesn't exist, make it
+                let id = self.nodes.len();
+                self.nodes.push(GraphNode::new(node.clone(), self.initial_score()));
+                self.node_positions.insert(node, id);
+                self.nodes_with_incoming = None; // new nodes have no edges 
+                id // return id
+            }
+        }
+    }
+
+    /// score a brand new node starts on
+    fn initial_score(&self) -> f64 {
+        match self.scores_deferred {
+            true => 0f64,
+            false => 1f64 - self.damping,
+        }
+    }
+
+    /// start maintaining scores if a degree only graph is about to be
+    /// calculated for the first time
+    fn ensure_scores(&mut self) {
+        if self.scores_deferred {
+            self.scores_deferred = false;
+            self.reset_scores();
+        }
+    }
+
+    /// Get or create a node and keep working with it in one borrow, like
+    /// HashMap's entry api
+    pub fn node_entry(&mut self, node: T) -> NodeEntry<'_, T> {
+        let inserted = !self.node_positions.contains_key(&node);
+        let id = self.get_or_create_node(node);
+        NodeEntry {
+            graph: self,
+            id,
+            inserted,
+        }
+    }
+
+    /// Look up a node's id without ever creating it
+    pub fn try_node_id(&self, node: &T) -> Option<usize> {
+        self.node_positions.get(node).copied()
+    }
+
+    /// Add the same edge count times in one go, same as calling add_edge in a
+    /// loop (a count of 0 doesn't even create the nodes)
+    pub fn add_edge_with_multiplicity(&mut self, source: T, target: T, count: usize) -> &mut Self {
+        if count == 0 {
+            return self;
+        }
+
+        let (source, target) = self.endpoints(source, target);
+        self.link(source, target, EdgeMeta::default(), count);
+        self
+    }
+
+    /// Add an edge tagged with a relation type, see set_relation_weight
+    pub fn add_typed_edge<R: Into<String>>(&mut self, source: T, target: T, relation: R) -> &mut Self {
+        let relation = self.relation_id(relation.into());
+        let (source, target) = self.endpoints(source, target);
+        self.link(source, target, EdgeMeta { relation: Some(relation), ..EdgeMeta::default() }, 1);
+        self
+    }
+
+    /// Add an edge stamped with the time it happened, see calculate_windowed.
+    /// outside of windowed runs it's a plain edge
+    pub fn add_timed_edge(&mut self, source: T, target: T, timestamp: u64) -> &mut Self {
+        let (source, target) = self.endpoints(source, target);
+        self.link(source, target, EdgeMeta { timestamp: Some(timestamp), ..EdgeMeta::default() }, 1);
+        self
+    }
+
+    /// Add an edge that carries weight instead of 1.0 when its source
+    /// splits up its score. panics if weight is negative or not finite
+    pub fn add_weighted_edge(&mut self, source: T, target: T, weight: f64) -> &mut Self {
+        assert!(weight.is_finite() && weight >= 0f64, "{weight} needs to be finite and non-negative");
+
+        let (source, target) = self.endpoints(source, target);
+        self.link(source, target, EdgeMeta { weight, ..EdgeMeta::default() }, 1);
+        self
+    }
+
+    /// Add a weighted edge both ways between a and b, for undirected graphs
+    ///
+    /// counts as two edges. a self loop (a == b) is only added once, with the
+    /// plain weight, so it isn't double counted against the node's other
+    /// edges. panics if weight is negative or not finite
+    pub fn add_weighted_undirected_edge(&mut self, a: T, b: T, weight: f64) -> &mut Self {
+        assert!(weight.is_finite() && weight >= 0f64, "{weight} needs to be finite and non-negative");
+
+        let (a, b) = self.endpoints(a, b);
+        let meta = EdgeMeta { weight, ..EdgeMeta::default() };
+        self.link(a, b, meta, 1);
+        if a != b {
+            self.link(b, a, meta, 1);
+        }
+        self
+    }
+
+    /// Weight every edge of a relation type when distributing score
+    ///
+    /// a node splits its score across its out edges in proportion to their
+    /// relation weights (1.0 unless set, plain edges always count as 1.0). a
+    /// weight of 0 makes the relation carry nothing
+    pub fn set_relation_weight<R: Into<String>>(&mut self, relation: R, weight: f64) -> Result<(), String> {
+        if !weight.is_finite() || weight < 0f64 {
+            return Err(format!("{weight} needs to be finite and non-negative"));
+        }
+
+        let relation = self.relation_id(relation.into());
+        self.relation_weights[relation] = weight;
+        Ok(())
+    }
+
+    /// get or create the id of a relation type
+    fn relation_id(&mut self, relation: String) -> usize {
+        match self.relations.get(&relation) {
+            Some(&id) => id,
+            None => {
+                let id = self.relation_weights.len();
+                self.relation_weights.push(1f64);
+                self.relations.insert(relation, id);
+                id
+            }
+        }
+    }
+
+    /// ids for both ends of a new edge, making room under the node cap first
+    fn endpoints(&mut self, source: T, target: T) -> (usize, usize) {
+        if self.eviction == EvictionPolicy::LowestDegree {
+            let keep = [self.try_node_id(&source), self.try_node_id(&target)];
+            let new = match source == target {
+                true => keep[0].is_none() as usize,
+                false => keep.iter().filter(|id| id.is_none()).count(),
+            };
+            let excess = (self.nodes.len() + new).saturating_sub(self.max_nodes);
+            if excess > 0 {
+                self.evict_lowest_degree(excess, &keep);
+            }
+        }
+
+        (self.get_or_create_node(source), self.get_or_create_node(target))
+    }
+
+    /// drop the count least connected nodes, apart from the ones in keep
+    fn evict_lowest_degree(&mut self, count: usize, keep: &[Option<usize>]) {
+        let mut candidates = (0..self.nodes.len())
+            .filter(|id| !keep.contains(&Some(*id)))
+            .collect::<Vec<usize>>();
+        // stable, so ties go to the oldest node
+        candidates.sort_by_key(|id| self.nodes[*id].incoming_edges.len() + self.nodes[*id].outgoing_edges.len());
+
+        let mut evicted = vec![false; self.nodes.len()];
+        for id in candidates.into_iter().take(count) {
+            evicted[id] = true;
+        }
+
+        let mut survivors = Vec::new();
+        let mapping = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, n)| match evicted[id] {
+                true => None,
+                false => {
+                    survivors.push(n.detached());
+                    Some(survivors.len() - 1)
+                }
+            })
+            .collect::<Vec<Option<usize>>>();
+        self.remap(&mapping, survivors);
+    }
+
+    /// record count copies of an edge between two existing ids
+    fn link(&mut self, source: usize, target: usize, meta: EdgeMeta, count: usize) {
+        self.nodes[source].outgoing_edges.extend(std::iter::repeat_n(target, count));
+        let target = &mut self.nodes[target];
+        target.incoming_edges.extend(std::iter::repeat_n(source, count));
+        target.incoming_meta.extend(std::iter::repeat_n(meta, count));
+        self.edges += count;
+        self.nodes_with_incoming = None;
+    }
+
+    /// Pre-register nodes so they get ids in iteration order
+    ///
+    /// call this before adding edges to get stable ids no matter what order
+    /// the edges show up in. nodes that already exist keep their id
+    pub fn register_nodes<I: IntoIterator<Item = T>>(&mut self, nodes: I) {
+        for node in nodes {
+            self.get_or_create_node(node);
+        }
+    }
+
+    /// adding nodes to the graph. 
+    /// returns the graph so calls can be chained
+    pub fn add_edge(&mut self, source: T, target: T) -> &mut Self {
+        let (source, target) = self.endpoints(source, target);
+        // this is a directed graph
+        self.link(source, target, EdgeMeta::default(), 1);
+        self
+    }
+
+    /// Jaccard index of the two graphs' edge sets, shared (source, target)
+    /// pairs over all pairs seen in either
+    ///
+    /// edges are compared by node value and parallel edges collapse into one
+    /// pair. two edgeless graphs count as identical (1.0)
+    pub fn jaccard_similarity(&self, other: &Pagerank<T>) -> f64 {
+        let ours = self.edge_values().collect::<HashSet<(&T, &T)>>();
+        let theirs = other.edge_values().collect::<HashSet<(&T, &T)>>();
+
+        let shared = ours.intersection(&theirs).count();
+        let union = ours.len() + theirs.len() - shared;
+        match union {
+            0 => 1f64,
+            _ => shared as f64 / union as f64,
+        }
+    }
+
+    /// Work out the edges to add and remove to give self other's structure
+    ///
+    /// edges are compared by node value and parallel edges count one by one.
+    /// scores and edge relations aren't part of the patch
+    pub fn diff(&self, other: &Pagerank<T>) -> GraphPatch<T> {
+        let mut self_counts = HashMap::<(&T, &T), usize>::new();
+        for edge in self.edge_values() {
+            *self_counts.entry(edge).or_default() += 1;
+        }
+        let mut other_counts = HashMap::<(&T, &T), usize>::new();
+        for edge in other.edge_values() {
+            *other_counts.entry(edge).or_default() += 1;
+        }
+
+        // walking both edge lists in order keeps the patch deterministic
+        let mut removed = Vec::new();
+        for edge in self.edge_values() {
+            match other_counts.get_mut(&edge) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => removed.push((edge.0.clone(), edge.1.clone())),
+            }
+        }
+        let mut added = Vec::new();
+        for edge in other.edge_values() {
+            match self_counts.get_mut(&edge) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => added.push((edge.0.clone(), edge.1.clone())),
+            }
+        }
+
+        GraphPatch { added, removed }
+    }
+
+    /// Apply a patch from diff, removing edges first and then adding
+    ///
+    /// removals of edges that don't exist are skipped. nodes left without
+    /// edges stay in the graph and scores aren't touched
+    pub fn apply_patch(&mut self, patch: &GraphPatch<T>) {
+        for (source, target) in patch.removed.iter() {
+            if let (Some(source), Some(target)) = (self.try_node_id(source), self.try_node_id(target)) {
+                self.unlink(source, target);
+            }
+        }
+        for (source, target) in patch.added.iter() {
+            self.add_edge(source.clone(), target.clone());
+        }
+    }
+
+    /// Every edge with the weight it carries when ranking (edge weight times
+    /// relation weight), heaviest first
+    pub fn edges_by_weight(&self) -> Vec<(&T, &T, f64)> {
+        let mut edges = self
+            .nodes
+            .iter()
+            .flat_map(|n| {
+                n.incoming_edges
+                    .iter()
+                    .zip(n.incoming_meta.iter())
+                    .map(move |(source, meta)| (&self.nodes[*source].node, &n.node, self.edge_weight(meta)))
+            })
+            .collect::<Vec<(&T, &T, f64)>>();
+
+        edges.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+        edges
+    }
+
+    /// Every edge as (source, source score, target, target score), grouped
+    /// by target. parallel edges come out once per copy
+    pub fn edges_with_scores(&self) -> impl Iterator<Item = (&T, f64, &T, f64)> + '_ {
+        self.edge_ids().map(|(source, target)| {
+            let (source, target) = (&self.nodes[source], &self.nodes[target]);
+            (&source.node, source.score, &target.node, target.score)
+        })
+    }
+
+    /// every edge as (source, target) ids, grouped by target
+    fn edge_ids(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(target, n)| n.incoming_edges.iter().map(move |source| (*source, target)))
+    }
+
+    /// every edge as (source, target) values, grouped by target
+    fn edge_values(&self) -> impl Iterator<Item = (&T, &T)> + '_ {
+        self.edge_ids()
+            .map(|(source, target)| (&self.nodes[source].node, &self.nodes[target].node))
+    }
+
+    /// drop one copy of an edge between two ids, handing back its metadata
+    fn unlink(&mut self, source: usize, target: usize) -> Option<EdgeMeta> {
+        let position = self.nodes[target].incoming_edges.iter().position(|id| *id == source)?;
+        Some(self.unlink_at(target, position))
+    }
+
+    /// drop the edge at position in target's incoming edges, the right copy
+    /// even among parallel ones
+    fn unlink_at(&mut self, target: usize, position: usize) -> EdgeMeta {
+        let n = &mut self.nodes[target];
+        let source = n.incoming_edges.remove(position);
+        let meta = n.incoming_meta.remove(position);
+        let outgoing = &mut self.nodes[source].outgoing_edges;
+        if let Some(position) = outgoing.iter().position(|id| *id == target) {
+            outgoing.remove(position);
+        }
+        self.edges -= 1;
+        self.nodes_with_incoming = None;
+        meta
+    }
+
+    /// Merge nodes that map to the same key
+    ///
+    /// the first inserted node of each group survives and takes over the
+    /// edges of the rest (parallel edges are kept, so counts add up). the
+    /// merged away values stop resolving and every unpinned score is reset.
+    /// survivors keep their own per-node settings (pin, weight, sink, ...)
+    pub fn deduplicate_by<K, F>(&mut self, key_fn: F)
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        let mut keys = HashMap::<K, usize>::new();
+        let mut survivors = Vec::new();
+
+        let mapping = self
+            .nodes
+            .iter()
+            .map(|n| {
+                let next = survivors.len();
+                let id = *keys.entry(key_fn(&n.node)).or_insert(next);
+                if id == next {
+                    let mut survivor = n.detached();
+                    if !survivor.pinned {
+                        survivor.score = self.initial_score();
+                    }
+                    survivors.push(survivor);
+                }
+                Some(id)
+            })
+            .collect::<Vec<Option<usize>>>();
+
+        self.remap(&mapping, survivors);
+    }
+
+    /// Rank every weakly connected component on its own
+    ///
+    /// each component runs to convergence separately with its teleport
+    /// normalized over just that component, then its scores get scaled to
+    /// sum to 1. that keeps big components from soaking up all the mass and
+    /// makes scores comparable inside a community (not across them). nodes get
+    /// tagged with their component, see component_of. returns the most
+    /// iterations any component needed
+    pub fn calculate_per_component(&mut self, convergence: f64) -> i32 {
+        let components = self.weak_components();
+        let mut members = Vec::<Vec<usize>>::new();
+        for (id, component) in components.iter().enumerate() {
+            if *component == members.len() {
+                members.push(Vec::new());
+            }
+            members[*component].push(id);
+            self.nodes[id].component = Some(*component);
+        }
+
+        let mut iterations = 0;
+        let mut outcome = RunOutcome::Converged;
+        for ids in members.iter() {
+            let mut sub = self.subgraph(ids);
+            iterations = iterations.max(sub.calculate_with_convergence(convergence));
+            if let Some(sub_outcome) = sub.last_outcome.filter(|o| *o != RunOutcome::Converged) {
+                outcome = sub_outcome;
+            }
+
+            let total = sub.nodes.iter().map(|n| n.score).sum::<f64>();
+            for (sub_id, id) in ids.iter().enumerate() {
+                let score = sub.nodes[sub_id].score;
+                self.nodes[*id].score = if total > 0f64 { score / total } else { score };
+            }
+        }
+
+        self.last_outcome = Some(outcome);
+        iterations
+    }
+
+    /// Which weak component a node was put in by the last
+    /// calculate_per_component, components are numbered by their first node
+    pub fn component_of(&self, node: &T) -> Option<usize> {
+        self.node_positions.get(node).and_then(|id| self.nodes[*id].component)
+    }
+
+    /// weakly connected component of every node, numbered in order of the
+    /// lowest id they contain
+    fn weak_components(&self) -> Vec<usize> {
+        let mut components = vec![usize::MAX; self.nodes.len()];
+        let mut next = 0;
+
+        for start in 0..self.nodes.len() {
+            if components[start] != usize::MAX {
+                continue;
+            }
+            components[start] = next;
+            let mut queue = VecDeque::from([start]);
+            while let Some(id) = queue.pop_front() {
+                let n = &self.nodes[id];
+                for other in n.incoming_edges.iter().chain(n.outgoing_edges.iter()) {
+                    if components[*other] == usize::MAX {
+                        components[*other] = next;
+                        queue.push_back(*other);
+                    }
+                }
+            }
+            next += 1;
+        }
+        components
+    }
+
+    /// strongly connected component of every node (tarjan, without
+    /// recursion), numbered in order of the lowest id they contain
+    fn strong_components(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0usize; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut found = vec![usize::MAX; n];
+        let (mut next_index, mut next_found) = (0, 0);
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            // (node, position of the next out edge to look at)
+            let mut work = vec![(root, 0usize)];
+
+            while let Some((id, position)) = work.last().copied() {
+                if let Some(target) = self.nodes[id].outgoing_edges.get(position).copied() {
+                    work.last_mut().unwrap().1 += 1;
+                    if index[target] == usize::MAX {
+                        index[target] = next_index;
+                        low[target] = next_index;
+                        next_index += 1;
+                        stack.push(target);
+                        on_stack[target] = true;
+                        work.push((target, 0));
+                    } else if on_stack[target] {
+                        low[id] = low[id].min(index[target]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some((parent, _)) = work.last() {
+                    low[*parent] = low[*parent].min(low[id]);
+                }
+                if low[id] == index[id] {
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        found[member] = next_found;
+                        if member == id {
+                            break;
+                        }
+                    }
+                    next_found += 1;
+                }
+            }
+        }
+
+        // renumber by first member so the numbering doesn't depend on the
+        // order tarjan finishes components in
+        let mut renumbered = vec![usize::MAX; next_found];
+        let mut next = 0;
+        found
+            .into_iter()
+            .map(|component| {
+                if renumbered[component] == usize::MAX {
+                    renumbered[component] = next;
+                    next += 1;
+                }
+                renumbered[component]
+            })
+            .collect()
+    }
+
+    /// Split the graph into the classic bow-tie components around its
+    /// largest strongly connected component
+    ///
+    /// ties for the largest component go to the one holding the earliest
+    /// inserted node. every node gets exactly one class, see BowtieComponent
+    pub fn bowtie_classification(&self) -> HashMap<&T, BowtieComponent> {
+        let n = self.nodes.len();
+        let components = self.strong_components();
+        let mut sizes = HashMap::<usize, usize>::new();
+        for component in components.iter() {
+            *sizes.entry(*component).or_default() += 1;
+        }
+        // component numbers follow the first member, so the lowest wins ties
+        let largest = sizes
+            .into_iter()
+            .max_by_key(|(component, size)| (*size, std::cmp::Reverse(*component)));
+        let Some((core, _)) = largest else {
+            return HashMap::new();
+        };
+
+        let in_core = components.iter().map(|c| *c == core).collect::<Vec<bool>>();
+        let core_ids = (0..n).filter(|id| in_core[*id]).collect::<Vec<usize>>();
+        let from_core = self.reach(&core_ids, Direction::Outgoing, &vec![false; n]);
+        let to_core = self.reach(&core_ids, Direction::Incoming, &vec![false; n]);
+
+        let classified = (0..n).map(|id| from_core[id] || to_core[id]).collect::<Vec<bool>>();
+        let in_ids = (0..n).filter(|id| to_core[*id] && !in_core[*id]).collect::<Vec<usize>>();
+        let out_ids = (0..n).filter(|id| from_core[*id] && !in_core[*id]).collect::<Vec<usize>>();
+        let from_in = self.reach(&in_ids, Direction::Outgoing, &classified);
+        let to_out = self.reach(&out_ids, Direction::Incoming, &classified);
+
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| {
+                let class = if in_core[id] {
+                    BowtieComponent::Core
+                } else if to_core[id] {
+                    BowtieComponent::In
+                } else if from_core[id] {
+                    BowtieComponent::Out
+                } else if from_in[id] && to_out[id] {
+                    BowtieComponent::Tube
+                } else if from_in[id] || to_out[id] {
+                    BowtieComponent::Tendril
+                } else {
+                    BowtieComponent::Disconnected
+                };
+                (&node.node, class)
+            })
+            .collect()
+    }
+
+    /// every id reachable from sources (sources included) in the given
+    /// direction, never stepping onto a blocked id
+    fn reach(&self, sources: &[usize], direction: Direction, blocked: &[bool]) -> Vec<bool> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        for source in sources {
+            seen[*source] = true;
+            queue.push_back(*source);
+        }
+
+        while let Some(id) = queue.pop_front() {
+            let next = match direction {
+                Direction::Outgoing => &self.nodes[id].outgoing_edges,
+                Direction::Incoming => &self.nodes[id].incoming_edges,
+            };
+            for other in next.iter() {
+                if !seen[*other] && !blocked[*other] {
+                    seen[*other] = true;
+                    queue.push_back(*other);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Rank the condensation of the graph, where every strongly connected
+    /// component collapses into a single node
+    ///
+    /// the resulting dag has one edge between two components whenever any
+    /// member edge connects them, and gets ranked with this graph's damping
+    /// and iteration cap. returns each component's members (in insertion
+    /// order) with its score, highest first
+    pub fn condensation_pagerank(&self, convergence: f64) -> Vec<(Vec<&T>, f64)> {
+        let components = self.strong_components();
+        let count = components.iter().max().map_or(0, |max| max + 1);
+        let mut members = vec![Vec::new(); count];
+        for (id, component) in components.iter().enumerate() {
+            members[*component].push(&self.nodes[id].node);
+        }
+
+        let mut condensed = Pagerank::<usize>::new();
+        condensed.damping = self.damping;
+        condensed.max_iterations = self.max_iterations;
+        condensed.register_nodes(0..count);
+        let mut links = self
+            .edge_ids()
+            .map(|(source, target)| (components[source], components[target]))
+            .filter(|(source, target)| source != target)
+            .collect::<Vec<(usize, usize)>>();
+        links.sort_unstable();
+        links.dedup();
+        for (source, target) in links {
+            condensed.add_edge(source, target);
+        }
+        condensed.reset_scores();
+        condensed.calculate_with_convergence(convergence);
+
+        let mut members = members.into_iter().map(Some).collect::<Vec<Option<Vec<&T>>>>();
+        condensed
+            .nodes()
+            .into_iter()
+            .filter_map(|(component, score)| members[*component].take().map(|nodes| (nodes, score)))
+            .collect()
+    }
+
+    /// new graph holding just the given ids (in that order) and the edges
+    /// between them, with the same settings as this one
+    fn subgraph(&self, ids: &[usize]) -> Pagerank<T> {
+        let mut sub = self.empty_like();
+        let mut mapping = vec![None; self.nodes.len()];
+        for (sub_id, id) in ids.iter().enumerate() {
+            mapping[*id] = Some(sub_id);
+            let n = self.nodes[*id].detached();
+            sub.node_positions.insert(n.node.clone(), sub_id);
+            sub.nodes.push(n);
+        }
+
+        for (sub_target, id) in ids.iter().enumerate() {
+            let n = &self.nodes[*id];
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                if let Some(sub_source) = mapping[*source] {
+                    sub.link(sub_source, sub_target, *meta, 1);
+                }
+            }
+        }
+
+        sub.personalization = self.personalization.as_ref().map(|weights| {
+            ids.iter()
+                .map(|id| weights.get(*id).copied().unwrap_or(0f64))
+                .collect()
+        });
+        sub
+    }
+
+    /// copy of the graph (same ids, settings and scores) with every edge
+    /// pointing the other way
+    fn reversed(&self) -> Pagerank<T> {
+        let mut reversed = self.empty_like();
+        reversed.nodes = self.nodes.iter().map(|n| n.detached()).collect();
+        reversed.node_positions.clone_from(&self.node_positions);
+        reversed.personalization.clone_from(&self.personalization);
+        for (target, n) in self.nodes.iter().enumerate() {
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                reversed.link(target, *source, *meta, 1);
+            }
+        }
+        reversed
+    }
+
+    /// Hold out a random fraction of the edges, for link prediction style
+    /// evaluation
+    ///
+    /// returns a copy of the graph (every node, setting and score kept)
+    /// without the held out edges, plus those edges in graph order. the same
+    /// seed always picks the same edges. fraction is clamped to [0, 1] and
+    /// the held out count rounds to the nearest edge
+    pub fn split_edges(&self, fraction: f64, seed: u64) -> (Pagerank<T>, Vec<(T, T)>) {
+        // every edge as (target, position in its incoming edges)
+        let edges = self
+            .nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(target, n)| (0..n.incoming_edges.len()).map(move |position| (target, position)))
+            .collect::<Vec<(usize, usize)>>();
+        let count = (fraction.clamp(0f64, 1f64) * edges.len() as f64).round() as usize;
+        let mut held_out = SplitMix64(seed).sample(edges.len(), count);
+        held_out.sort_unstable();
+
+        // the copy keeps the same edge order. going backwards, removing
+        // an edge never shifts the position of one still to be removed
+        let mut train = self.subgraph(&(0..self.nodes.len()).collect::<Vec<usize>>());
+        for i in held_out.iter().rev() {
+            let (target, position) = edges[*i];
+            train.unlink_at(target, position);
+        }
+        let held_out = held_out
+            .into_iter()
+            .map(|i| {
+                let (target, position) = edges[i];
+                let source = self.nodes[target].incoming_edges[position];
+                (self.nodes[source].node.clone(), self.nodes[target].node.clone())
+            })
+            .collect();
+        (train, held_out)
+    }
+
+    /// How much each node's score moves when edges go missing, as the
+    /// standard deviation of its score over trials
+    ///
+    /// every trial drops drop_fraction of the edges (picked like split_edges)
+    /// and recalculates a copy, this graph is left alone. the same seed
+    /// gives the same answer, zero trials gives all zeros
+    pub fn robustness(&self, drop_fraction: f64, trials: usize, seed: u64, convergence: f64) -> HashMap<&T, f64> {
+        let mut rng = SplitMix64(seed);
+        let runs = (0..trials)
+            .map(|_| {
+                let (mut trial, _) = self.split_edges(drop_fraction, rng.next_u64());
+                trial.calculate_with_convergence(convergence);
+                trial.scores()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let count = trials.max(1) as f64;
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(id, n)| {
+                let mean = runs.iter().map(|scores| scores[id]).sum::<f64>() / count;
+                let variance = runs.iter().map(|scores| (scores[id] - mean).powi(2)).sum::<f64>() / count;
+                (&n.node, variance.sqrt())
+            })
+            .collect()
+    }
+
+    /// Score a new node would end up with if every node in incoming_from
+    /// linked to it, without touching this graph
+    ///
+    /// works on a copy that starts from the current scores, so the sources'
+    /// extra out edge thins out what their other targets get too. a node
+    /// listed twice links twice, unknown nodes are skipped
+    pub fn predict_rank(&self, incoming_from: &[T], convergence: f64) -> f64 {
+        let sources = incoming_from
+            .iter()
+            .filter_map(|node| self.node_positions.get(node).copied())
+            .collect::<Vec<usize>>();
+        // the new node never gets looked up by value, any T will do
+        let Some(placeholder) = self.nodes.first().map(|n| n.node.clone()) else {
+            return 1f64 - self.damping;
+        };
+
+        let mut what_if = self.subgraph(&(0..self.nodes.len()).collect::<Vec<usize>>());
+        let id = what_if.nodes.len();
+        what_if.nodes.push(GraphNode::new(placeholder, self.initial_score()));
+        for source in sources {
+            what_if.link(source, id, EdgeMeta::default(), 1);
+        }
+        what_if.calculate_with_convergence(convergence);
+        what_if.nodes[id].score
+    }
+
+    /// graph with no nodes but every setting copied over
+    fn empty_like(&self) -> Pagerank<T> {
+        Pagerank::<T> {
+            damping: self.damping,
+            max_iterations: self.max_iterations,
+            stall_epsilon: self.stall_epsilon,
+            stall_window: self.stall_window,
+            iteration_mode: self.iteration_mode,
+            restart_every: self.restart_every,
+            dangling_handling: self.dangling_handling,
+            track_stabilization: self.track_stabilization,
+            scores_deferred: self.scores_deferred,
+            max_incoming: self.max_incoming,
+            eviction: self.eviction,
+            max_nodes: self.max_nodes,
+            sort_epsilon: self.sort_epsilon,
+            laziness: self.laziness,
+            auto_stabilize: self.auto_stabilize,
+            snapshot_iterations: self.snapshot_iterations.clone(),
+            relations: self.relations.clone(),
+            relation_weights: self.relation_weights.clone(),
+            node_positions: HashMap::with_hasher(self.node_positions.hasher().clone()),
+            ..Pagerank::new()
+        }
+    }
+
+    /// Drop every node scoring below threshold, along with its edges
+    ///
+    /// survivors get packed into new ids (keeping their relative order) and
+    /// keep their current scores, recalculating is up to the caller
+    pub fn retain_above(&mut self, threshold: f64) {
+        let mut survivors = Vec::new();
+        let mapping = self
+            .nodes
+            .iter()
+            .map(|n| match n.score < threshold {
+                true => None,
+                false => {
+                    survivors.push(n.detached());
+                    Some(survivors.len() - 1)
+                }
+            })
+            .collect::<Vec<Option<usize>>>();
+
+        self.remap(&mapping, survivors);
+    }
+
+    /// rebuild the graph onto a new id space
+    ///
+    /// mapping sends every old id to its new id (None drops the node along
+    /// with its edges), nodes holds the edgeless node for each new id
+    fn remap(&mut self, mapping: &[Option<usize>], nodes: Vec<GraphNode<T>>) {
+        let old = std::mem::replace(&mut self.nodes, nodes);
+        self.edges = 0;
+
+        for (target, n) in old.iter().enumerate() {
+            let Some(target) = mapping[target] else {
+                continue;
+            };
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                if let Some(source) = mapping[*source] {
+                    self.link(source, target, *meta, 1);
+                }
+            }
+        }
+
+        if let Some(weights) = &self.personalization {
+            let mut remapped = vec![0f64; self.nodes.len()];
+            for (id, weight) in weights.iter().enumerate() {
+                if let Some(id) = mapping[id] {
+                    remapped[id] += weight;
+                }
+            }
+            // nothing left to teleport to, fall back to uniform
+            self.personalization = match remapped.iter().sum::<f64>() > 0f64 {
+                true => Some(remapped),
+                false => None,
+            };
+        }
+
+        self.snapshots.clear(); // keyed by the old ids
+        for column in self.named_scores.values_mut() {
+            let mut remapped = vec![1f64 - self.damping; self.nodes.len()];
+            for (id, score) in column.iter().enumerate().rev() {
+                if let Some(id) = mapping[id] {
+                    remapped[id] = *score; // going backwards so the first node wins
+                }
+            }
+            *column = remapped;
+        }
+
+        self.node_positions.clear();
+        self.node_positions
+            .extend(self.nodes.iter().enumerate().map(|(id, n)| (n.node.clone(), id)));
+        self.nodes_with_incoming = None;
+    }
+
+    /// Hold a node at a fixed score while the rest of the graph iterates
+    ///
+    /// pinned nodes still pass their score on to their out-neighbors, they
+    /// just never get updated themselves. creates the node if needed
+    pub fn pin_score(&mut self, node: T, score: f64) {
+        let id = self.get_or_create_node(node);
+        self.nodes[id].score = score;
+        self.nodes[id].pinned = true;
+    }
+
+    /// Let a pinned node be updated again, its score stays where it was
+    pub fn unpin_score(&mut self, node: T) {
+        if let Some(id) = self.node_positions.get(&node) {
+            self.nodes[*id].pinned = false;
+        }
+    }
+
+    /// Get node score
+    pub fn get_score(&self, node: T) -> Option<f64> {
+        self.node_positions
+            .get(&node)
+            .map(|id| self.nodes[*id].score)
+    }
+
+    /// Get node's number of incoming edges
+    pub fn get_incoming_edges(&self, node: T) -> Option<usize> {
+        self.node_positions
+            .get(&node)
+            .map(|id| self.nodes[*id].incoming_edges.len())
+    }
+
+    /// Get nodes number of outgoing edges
+    pub fn get_outgoing_edges(&self, node: T) -> Option<usize> {
+        self.node_positions
+            .get(&node)
+            .map(|id| self.nodes[*id].outgoing_edges.len())
+    }
+
+    /// How many nodes a node's score actually gets split between in a step
+    ///
+    /// the real out-degree for nodes with weighted out edges. a dangling node
+    /// counts the dangling sinks if there are any, every node the teleport
+    /// can land on (so not excluded or zero personalization ones) under
+    /// DanglingHandling::Teleport and 0.0 when its rank leaks. absorbing
+    /// nodes count 1.0 for the loop back to themselves. unknown nodes get 0.0
+    pub fn effective_out_degree(&self, node: &T) -> f64 {
+        let Some(id) = self.node_positions.get(node).copied() else {
+            return 0f64;
+        };
+        if self.nodes[id].absorbing {
+            return 1f64;
+        }
+        if self.out_weights()[id] > 0f64 {
+            return self.nodes[id].outgoing_edges.len() as f64;
+        }
+
+        match self.nodes.iter().filter(|n| n.dangling_sink).count() {
+            0 if self.dangling_handling == DanglingHandling::Teleport => {
+                self.teleport().iter().filter(|weight| **weight > 0f64).count() as f64
+            }
+            0 => 0f64,
+            sinks => sinks as f64,
+        }
+    }
+
+    /// How much every incoming edge adds to the node's score
+    ///
+    /// each entry is the source's current score times damping (the node's
+    /// override if set) times the share of it that flows along the edge.
+    /// edges out of absorbing nodes carry nothing and are left out, while an
+    /// absorbing node gets an entry for its own score it holds on to. once
+    /// converged these
+    /// plus the teleport term (and any dangling sink share) add up to the
+    /// node's score
+    pub fn score_breakdown(&self, node: T) -> Option<Vec<(&T, f64)>> {
+        let id = *self.node_positions.get(&node)?;
+        let out_weights = self.flow_out_weights();
+        let n = &self.nodes[id];
+
+        let mut shares = n
+            .incoming_edges
+            .iter()
+            .zip(n.incoming_meta.iter())
+            .take(self.max_incoming)
+            .filter(|(source, _)| !self.nodes[**source].absorbing)
+            .map(|(source, meta)| (&self.nodes[*source].node, self.edge_flow(*source, id, meta, &out_weights)))
+            .collect::<Vec<(&T, f64)>>();
+        if n.absorbing {
+            shares.push((&n.node, n.damping.unwrap_or(self.damping) * n.score));
+        }
+        Some(shares)
+    }
+
+    /// out weights as the update sees them, absorbing nodes send nothing
+    fn flow_out_weights(&self) -> Vec<f64> {
+        self.out_weights_where(&|source, _, _| !self.nodes[source].absorbing)
+    }
+
+    /// score an edge carries from source into target in a step, as in
+    /// next_scores. out_weights should come from flow_out_weights
+    fn edge_flow(&self, source: usize, target: usize, meta: &EdgeMeta, out_weights: &[f64]) -> f64 {
+        match self.nodes[source].absorbing {
+            true => 0f64,
+            false => {
+                let damping = self.nodes[target].damping.unwrap_or(self.damping);
+                damping * self.nodes[source].score * self.edge_share(source, meta, out_weights)
+            }
+        }
+    }
+
+    /// Rank mass a node hands to others along its out edges in a step, its
+    /// score times damping (using each target's damping override if set)
+    ///
+    /// the dual of score_breakdown, see influence_breakdown for the per
+    /// target split. a node without out edges passes nothing along links so
+    /// gets 0.0. None if the node doesn't exist
+    pub fn outgoing_influence(&self, node: T) -> Option<f64> {
+        self.influence_breakdown(node).map(|shares| shares.iter().map(|(_, share)| share).sum())
+    }
+
+    /// outgoing_influence split per out edge as (target, mass), targets in
+    /// the order they were first linked. parallel edges get one entry per
+    /// copy. absorbing nodes hand nothing on so get an empty list
+    pub fn influence_breakdown(&self, node: T) -> Option<Vec<(&T, f64)>> {
+        let id = *self.node_positions.get(&node)?;
+        if self.nodes[id].absorbing {
+            return Some(Vec::new());
+        }
+        let out_weights = self.flow_out_weights();
+
+        let mut seen = HashSet::new();
+        let targets = self.nodes[id].outgoing_edges.iter().filter(|target| seen.insert(**target)).copied().collect::<Vec<usize>>();
+
+        let mut shares = Vec::new();
+        for target in targets {
+            let n = &self.nodes[target];
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                if *source == id {
+                    shares.push((&n.node, self.edge_flow(id, target, meta, &out_weights)));
+                }
+            }
+        }
+        Some(shares)
+    }
+
+    /// Raw source ids of every edge pointing at the node with this id
+    ///
+    /// no lookup and no allocation, pair it with try_node_id for custom
+    /// traversals. parallel edges show up once per copy
+    pub fn incoming_edge_ids(&self, id: usize) -> Option<&[usize]> {
+        self.nodes.get(id).map(|n| n.incoming_edges.as_slice())
+    }
+
+    /// Get the target nodes of every edge leaving node
+    pub fn neighbors(&self, node: T) -> Option<Vec<&T>> {
+        self.node_positions.get(&node).map(|id| {
+            self.nodes[*id]
+                .outgoing_edges
+                .iter()
+                .map(|target| &self.nodes[*target].node)
+                .collect()
+        })
+    }
+
+    /// Sum of the current scores of a node's distinct neighbors in the given
+    /// direction, a second order "connected to important nodes" signal
+    ///
+    /// parallel edges count the neighbor once, a self loop counts the node
+    /// itself. None if the node doesn't exist
+    pub fn neighbor_rank_sum(&self, node: T, direction: Direction) -> Option<f64> {
+        let n = &self.nodes[*self.node_positions.get(&node)?];
+        let mut neighbors = match direction {
+            Direction::Outgoing => n.outgoing_edges.clone(),
+            Direction::Incoming => n.incoming_edges.clone(),
+        };
+        neighbors.sort_unstable();
+        neighbors.dedup();
+
+        Some(neighbors.into_iter().map(|id| self.nodes[id].score).sum())
+    }
+
+    /// In-degree where every incoming edge counts its source's current score
+    /// instead of 1, a "quality of incoming links" measure
+    ///
+    /// unlike neighbor_rank_sum parallel edges count once per copy. None if
+    /// the node doesn't exist
+    pub fn weighted_in_degree(&self, node: T) -> Option<f64> {
+        let n = &self.nodes[*self.node_positions.get(&node)?];
+        Some(n.incoming_edges.iter().map(|source| self.nodes[*source].score).sum())
+    }
+
+    /// How many times the edge from source to target was added, 0 if it
+    /// never was or either node doesn't exist
+    pub fn edge_multiplicity(&self, source: &T, target: &T) -> usize {
+        match (self.node_positions.get(source), self.node_positions.get(target)) {
+            (Some(source), Some(target)) => self.nodes[*target].incoming_edges.iter().filter(|id| *id == source).count(),
+            _ => 0,
+        }
+    }
+
+    /// Get the source nodes of every edge pointing at node
+    pub fn incoming_neighbors(&self, node: T) -> Option<Vec<&T>> {
+        self.node_positions.get(&node).map(|id| {
+            self.nodes[*id]
+                .incoming_edges
+                .iter()
+                .map(|source| &self.nodes[*source].node)
+                .collect()
+        })
+    }
+
+    /// len of all edges
+    pub fn len_nodes_with_incoming_edges(&mut self) -> usize {
+        if let Some(n) = self.nodes_with_incoming {
+            return n;
+        }
+
+        let mut total = 0 as usize;
+        for node in self.nodes.iter() {
+            if node.incoming_edges.len() > 0 {
+                total += 1;
+            }
+        }
+
+        self.nodes_with_incoming = Some(total);
+
+        total
+    }
+    /// current score of every node, in id order
+    fn scores(&self) -> Vec<f64> {
+        self.nodes.iter().map(|n| n.score).collect()
+    }
+
+    /// write a score buffer back onto the nodes
+    fn set_scores(&mut self, scores: &[f64]) {
+        self.nodes
+            .iter_mut()
+            .zip(scores.iter())
+            .for_each(|(n, score)| n.score = *score);
+    }
+
+    /// teleport weight of each node (personalization times node weight),
+    /// scaled so the weights sum to the node count. a uniform teleport is all
+    /// ones
+    fn teleport(&self) -> Vec<f64> {
+        self.scaled_teleport(|id| match &self.personalization {
+            Some(weights) => weights.get(id).copied().unwrap_or(0f64),
+            None => 1f64,
+        })
+    }
+
+    /// teleport built from a personalization weight per id, see teleport
+    fn scaled_teleport<P: Fn(usize) -> f64>(&self, personal: P) -> Vec<f64> {
+        let raw = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, n)| match n.teleport_excluded {
+                true => 0f64,
+                false => personal(id) * n.weight,
+            })
+            .collect::<Vec<f64>>();
+
+        let total = raw.iter().sum::<f64>();
+        if total <= 0f64 {
+            return raw; // nothing left to teleport to
+        }
+        let scale = self.nodes.len() as f64 / total;
+        raw.into_iter().map(|w| w * scale).collect()
+    }
+
+    /// weight an edge carries when score gets distributed
+    fn edge_weight(&self, meta: &EdgeMeta) -> f64 {
+        meta.weight * meta.relation.map_or(1f64, |relation| self.relation_weights[relation])
+    }
+
+    /// total weight of every node's out edges
+    fn out_weights(&self) -> Vec<f64> {
+        self.out_weights_where(&|_, _, _| true)
+    }
+
+    /// total weight of every node's out edges that pass keep(source, target, meta)
+    fn out_weights_where<K: Fn(usize, usize, &EdgeMeta) -> bool>(&self, keep: &K) -> Vec<f64> {
+        let mut out_weights = vec![0f64; self.nodes.len()];
+        for (target, n) in self.nodes.iter().enumerate() {
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                if keep(*source, target, meta) {
+                    out_weights[*source] += self.edge_weight(meta);
+                }
+            }
+        }
+        out_weights
+    }
+
+    /// fraction of the source's score that flows along one of its edges
+    fn edge_share(&self, source: usize, meta: &EdgeMeta, out_weights: &[f64]) -> f64 {
+        match out_weights[source] > 0f64 {
+            true => self.edge_weight(meta) / out_weights[source],
+            false => 0f64, // every out edge weighs nothing
+        }
+    }
+
+    /// score every node picks up from dangling nodes in a step
+    ///
+    /// with sinks the dangling mass gets split evenly between them, without
+    /// sinks it leaks out of the graph or follows the teleport vector,
+    /// depending on the dangling handling
+    fn dangling_shares<D: Fn(usize) -> bool>(&self, old: &[f64], teleport: &[f64], is_dangling: D) -> Vec<f64> {
+        let mut shares = vec![0f64; self.nodes.len()];
+        let sinks = self.nodes.iter().filter(|n| n.dangling_sink).count();
+        if sinks == 0 && self.dangling_handling == DanglingHandling::Leak {
+            return shares;
+        }
+
+        let mass = (0..self.nodes.len())
+            .filter(|id| is_dangling(*id))
+            .map(|id| old[id])
+            .sum::<f64>();
+        for (id, n) in self.nodes.iter().enumerate() {
+            if sinks == 0 {
+                shares[id] = mass * teleport[id] / self.nodes.len() as f64;
+            } else if n.dangling_sink {
+                shares[id] = mass / sinks as f64;
+            }
+        }
+        shares
+    }
+
+    /// one sweep of the update rule
+    ///
+    /// jacobi reads only from old, gauss-seidel reads scores already updated
+    /// earlier in this same sweep
+    fn next_scores(&self, old: &[f64]) -> Vec<f64> {
+        self.next_scores_where(old, &|_, _, _| true)
+    }
+
+    /// one sweep of the update rule as if only the edges passing
+    /// keep(source, target, meta) existed
+    fn next_scores_where<K: Fn(usize, usize, &EdgeMeta) -> bool>(&self, old: &[f64], keep: &K) -> Vec<f64> {
+        self.next_scores_with(old, &self.teleport(), keep)
+    }
+
+    /// one sweep of the update rule with the given teleport vector
+    fn next_scores_with<K>(&self, old: &[f64], teleport: &[f64], keep: &K) -> Vec<f64>
+    where
+        K: Fn(usize, usize, &EdgeMeta) -> bool,
+    {
+        let mut new = old.to_vec();
+        let keep = &|source: usize, target, meta: &EdgeMeta| !self.nodes[source].absorbing && keep(source, target, meta);
+        let out_weights = self.out_weights_where(keep);
+        let dangling = self.dangling_shares(old, teleport, |id| out_weights[id] <= 0f64 && !self.nodes[id].absorbing);
+
+        let restart = self.restart_due();
+        for id in 0..self.nodes.len() {
+            let current = match self.iteration_mode {
+                IterationMode::Jacobi => old,
+                IterationMode::GaussSeidel => &new,
+            };
+            let incoming = self.incoming_sum(id, current, &out_weights, keep) + dangling[id];
+            new[id] = self.updated_score(id, old, incoming, teleport[id], restart);
+        }
+
+        new
+    }
+
+    /// does the coming step get forced to restart? a forced restart is just a
+    /// step where nobody follows a link
+    fn restart_due(&self) -> bool {
+        matches!(self.restart_every, Some(k) if (self.steps_taken + 1).is_multiple_of(k as u64))
+    }
+
+    /// a node's next score given the rank reaching it along links (dangling
+    /// share included), the last part of every pagerank style update
+    ///
+    /// takes care of pins, absorbing nodes holding on to their score, damping
+    /// overrides, forced restarts and laziness
+    fn updated_score(&self, id: usize, old: &[f64], incoming: f64, teleport: f64, restart: bool) -> f64 {
+        let n = &self.nodes[id];
+        if n.pinned {
+            return old[id];
+        }
+        let incoming = match n.absorbing {
+            true => incoming + old[id],
+            false => incoming,
+        };
+        let damping = match restart {
+            true => 0f64,
+            false => n.damping.unwrap_or(self.damping),
+        };
+        let update = (1f64 - damping) * teleport + damping * incoming;
+        (1f64 - self.laziness) * old[id] + self.laziness * update
+    }
+
+    /// score flowing into a node along its kept incoming edges (up to the cap)
+    fn incoming_sum<K>(&self, target: usize, current: &[f64], out_weights: &[f64], keep: &K) -> f64
+    where
+        K: Fn(usize, usize, &EdgeMeta) -> bool,
+    {
+        let n = &self.nodes[target];
+        // define a closure over the nodes 
+        // god fp is rad
+        n.incoming_edges
+            .iter()
+            .zip(n.incoming_meta.iter())
+            .take(self.max_incoming)
+            .filter(|(node, meta)| keep(**node, target, meta))
+            .map(|(node, meta)| current[*node] * self.edge_share(*node, meta, out_weights))
+            .sum::<f64>()
+    }
+
+    /// run one step and measure it with the given metric
+    fn step_with<F>(&mut self, metric: &mut F) -> f64
+    where
+        F: FnMut(&[f64], &[f64]) -> f64,
+    {
+        self.step_by(&mut |pr: &Self, old: &[f64]| pr.next_scores(old), metric)
+    }
+
+    /// run one step of a custom update rule and measure it with the metric
+    fn step_by<N, F>(&mut self, next: &mut N, metric: &mut F) -> f64
+    where
+        N: FnMut(&Self, &[f64]) -> Vec<f64>,
+        F: FnMut(&[f64], &[f64]) -> f64,
+    {
+        self.ensure_scores();
+        let old = self.scores();
+        let new = next(self, &old);
+        let residual = metric(&old, &new);
+        self.set_scores(&new);
+        self.steps_taken += 1;
+        self.last_residual = Some(residual);
+        residual
+    }
+
+    /// Run a single iteration, returns the residual
+    pub fn calculate_step(&mut self) -> f64 {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        self.step_with(&mut |old, new| residual(old, new, with_incoming))
+    }
+
+    /// Run exactly this many iterations without ever measuring convergence
+    ///
+    /// skipping the residual saves a pass over the scores per step, so this
+    /// is the cheapest way to spend a fixed budget. doesn't touch
+    /// last_residual or the last run outcome
+    pub fn run_fixed(&mut self, iterations: u32) {
+        self.ensure_scores();
+        for _ in 0..iterations {
+            let old = self.scores();
+            let new = self.next_scores(&old);
+            self.set_scores(&new);
+            self.steps_taken += 1;
+        }
+    }
+
+    /// calculate pagerank with a custom stopping criterion
+    ///
+    /// the metric gets the old and new score buffers (in node id order) after
+    /// every step and we stop once whatever it returns drops below threshold
+    pub fn calculate_with<F>(&mut self, threshold: f64, metric: F) -> i32
+    where
+        F: FnMut(&[f64], &[f64]) -> f64,
+    {
+        self.iterate(threshold, metric, |_| true)
+    }
+
+    /// calculate pagerank with custom convergence, telling the reporter
+    /// about every step (the converging one included)
+    pub fn calculate_with_reporter(&mut self, convergence: f64, reporter: &mut dyn ProgressReporter) -> i32 {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        let mut step = 0;
+        self.calculate_with(convergence, |old, new| {
+            let residual = residual(old, new, with_incoming);
+            step += 1;
+            reporter.on_iteration(step, residual);
+            residual
+        })
+    }
+
+    /// the loop behind the calculate family
+    ///
+    /// keep_going is asked before every step (with the iterations done so far)
+    /// and stopping there counts as a truncated run just like the cap
+    fn iterate<F, C>(&mut self, threshold: f64, metric: F, keep_going: C) -> i32
+    where
+        F: FnMut(&[f64], &[f64]) -> f64,
+        C: FnMut(i32) -> bool,
+    {
+        self.iterate_by(threshold, |pr: &Self, old: &[f64]| pr.next_scores(old), metric, keep_going)
+    }
+
+    /// same loop as iterate but stepping with a custom update rule
+    fn iterate_by<N, F, C>(&mut self, threshold: f64, mut next: N, mut metric: F, mut keep_going: C) -> i32
+    where
+        N: FnMut(&Self, &[f64]) -> Vec<f64>,
+        F: FnMut(&[f64], &[f64]) -> f64,
+        C: FnMut(i32) -> bool,
+    {
+        let mut iterations = 0;
+        let mut previous: Option<f64> = None;
+        let mut flat_steps = 0;
+        self.ensure_scores();
+        for n in self.nodes.iter_mut() {
+            n.stabilized_at = None;
+        }
+        self.snapshots.clear();
+
+        loop {
+            if iterations >= self.max_iterations || !keep_going(iterations) {
+                self.last_outcome = Some(RunOutcome::Truncated);
+                break;
+            }
+            let before = self.track_stabilization.then(|| self.scores());
+            let residual = self.step_by(&mut next, &mut metric);
+            let step = iterations as u32 + 1;
+            if self.snapshot_iterations.contains(&step) {
+                self.snapshots.insert(step, self.scores());
+            }
+            if let Some(before) = before {
+                for (n, old) in self.nodes.iter_mut().zip(before) {
+                    n.stabilized_at = match (n.score - old).abs() < STABILIZATION_EPSILON {
+                        true => n.stabilized_at.or(Some(step)),
+                        false => None,
+                    };
+                }
+            }
+            if residual < threshold {
+                self.last_outcome = Some(RunOutcome::Converged);
+                break;
+            }
+
+            // watch for the residual sitting on a plateau
+            if let Some(previous) = previous {
+                if (previous - residual).abs() <= self.stall_epsilon * previous.abs() {
+                    flat_steps += 1;
+                } else {
+                    flat_steps = 0;
+                }
+            }
+            if self.stall_window > 0 && flat_steps >= self.stall_window {
+                self.last_outcome = Some(RunOutcome::Stalled);
+                break;
+            }
+
+            previous = Some(residual);
+            iterations += 1;
+        }
+        iterations
+    }
+
+    /// calculate pagerank using only the timed edges stamped within
+    /// [start, end]
+    ///
+    /// every other edge (untimed ones included) is left out of both the
+    /// contributions and the out-degrees, so a node whose edges all fall
+    /// outside the window is dangling for this run
+    pub fn calculate_windowed(&mut self, start: u64, end: u64, convergence: f64) -> i32 {
+        self.calculate_where(convergence, move |_, _, meta| {
+            meta.timestamp.is_some_and(|t| start <= t && t <= end)
+        })
+    }
+
+    /// calculate pagerank counting only the edges predicate(source, target)
+    /// accepts, without touching the stored graph
+    ///
+    /// the out-degrees (and out weights) only count accepted edges too, so a
+    /// node whose edges all get rejected is dangling for this run. the
+    /// predicate runs once per distinct node pair, parallel edges share its
+    /// answer
+    pub fn calculate_filtered<F: Fn(&T, &T) -> bool>(&mut self, predicate: F, convergence: f64) -> i32 {
+        let rejected = self
+            .outgoing_adjacency()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(source, targets)| targets.into_iter().map(move |target| (source, target)))
+            .filter(|(source, target)| !predicate(&self.nodes[*source].node, &self.nodes[*target].node))
+            .collect::<HashSet<(usize, usize)>>();
+
+        self.calculate_where(convergence, move |source, target, _| !rejected.contains(&(source, target)))
+    }
+
+    /// calculate pagerank with the teleport weights handed out fresh for
+    /// every step by teleport_fn(iteration), 0 for the first step
+    ///
+    /// the weights work like a personalization (times the node weights,
+    /// renormalized over the graph) that only lasts one step. nodes missing
+    /// from the map get 0, unknown nodes and negative or non-finite weights
+    /// are ignored. any static personalization is ignored for the run
+    pub fn calculate_with_dynamic_teleport<F>(&mut self, convergence: f64, mut teleport_fn: F) -> i32
+    where
+        F: FnMut(i32) -> HashMap<T, f64>,
+    {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        let mut iteration = 0;
+        self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| {
+                let weights = teleport_fn(iteration);
+                iteration += 1;
+                let teleport = pr.scaled_teleport(|id| {
+                    weights
+                        .get(&pr.nodes[id].node)
+                        .copied()
+                        .filter(|w| w.is_finite() && *w >= 0f64)
+                        .unwrap_or(0f64)
+                });
+                pr.next_scores_with(old, &teleport, &|_, _, _| true)
+            },
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        )
+    }
+
+    /// calculate as if only the edges passing keep(source, target, meta)
+    /// existed
+    fn calculate_where<K: Fn(usize, usize, &EdgeMeta) -> bool>(&mut self, convergence: f64, keep: K) -> i32 {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| pr.next_scores_where(old, &keep),
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        )
+    }
+
+    /// run state to hand back to restore_run once a probe is done
+    fn save_run(&self) -> RunState {
+        RunState {
+            scores: self.scores(),
+            stabilized_at: self.nodes.iter().map(|n| n.stabilized_at).collect(),
+            outcome: self.last_outcome,
+            residual: self.last_residual,
+            steps_taken: self.steps_taken,
+            laziness: self.laziness,
+            snapshots: self.snapshots.clone(),
+        }
+    }
+
+    fn restore_run(&mut self, state: RunState) {
+        self.set_scores(&state.scores);
+        for (n, stabilized_at) in self.nodes.iter_mut().zip(state.stabilized_at) {
+            n.stabilized_at = stabilized_at;
+        }
+        self.last_outcome = state.outcome;
+        self.last_residual = state.residual;
+        self.steps_taken = state.steps_taken;
+        self.laziness = state.laziness;
+        self.snapshots = state.snapshots;
+    }
+
+    /// How much every score would move if the edge were toggled, removing
+    /// one copy if it's there and adding it if it isn't
+    ///
+    /// calculates this graph as it is and a copy with the edge toggled, both
+    /// from the current scores, and hands back toggled minus original for
+    /// every node. the edge gets added to the copy like add_edge would, so
+    /// missing endpoints only ever exist there (and stay out of the map) and
+    /// a node the node cap evicts counts as dropping to 0. the scores and run
+    /// stats (snapshots and stabilization included) are put back afterwards
+    pub fn edge_sensitivity(&mut self, source: T, target: T, convergence: f64) -> HashMap<&T, f64> {
+        let mut toggled = self.subgraph(&(0..self.nodes.len()).collect::<Vec<usize>>());
+        match (self.try_node_id(&source), self.try_node_id(&target)) {
+            (Some(source), Some(target)) if self.nodes[target].incoming_edges.contains(&source) => {
+                toggled.unlink(source, target);
+            }
+            _ => {
+                toggled.add_edge(source, target);
+            }
+        }
+        toggled.calculate_with_convergence(convergence);
+
+        let saved = self.save_run();
+        self.calculate_with_convergence(convergence);
+        let original = self.scores();
+        self.restore_run(saved);
+
+        self.nodes
+            .iter()
+            .zip(original)
+            .map(|(n, before)| (&n.node, toggled.get_score(n.node.clone()).unwrap_or(0f64) - before))
+            .collect()
+    }
+
+    /// Replace the scores with Katz centrality, iterating
+    /// x = alpha * A^T x + beta until the residual drops below convergence
+    ///
+    /// every incoming edge (parallel ones once per copy) passes on alpha
+    /// times its weight (relation weight included) times its source's score,
+    /// so all paths count with attenuation instead of a normalized random
+    /// walk. this only converges for alpha below one over the weighted
+    /// adjacency's spectral radius, other alphas are an error and leave the
+    /// scores alone. the radius is bounded from above by the max weighted
+    /// in/out degree and a power iteration bound, so an accepted alpha always
+    /// converges (an alpha just under the limit can get turned down).
+    /// returns the iterations done
+    pub fn calculate_katz(&mut self, alpha: f64, beta: f64, convergence: f64) -> Result<i32, String> {
+        let radius = self.spectral_radius_bound();
+        if alpha.is_nan() || alpha < 0f64 || alpha * radius >= 1f64 {
+            return Err(format!("alpha {alpha} has to be in [0, 1 / {radius}) for katz to converge"));
+        }
+
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        Ok(self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| {
+                pr.nodes
+                    .iter()
+                    .map(|n| alpha * pr.weighted_incoming(n, old) + beta)
+                    .collect()
+            },
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        ))
+    }
+
+    /// Replace the scores with eigenvector centrality, pagerank without
+    /// damping or teleport: each node's score is proportional to the sum of
+    /// its in-neighbors' scores
+    ///
+    /// scores are scaled to unit length every step. it power iterates on
+    /// x + A^T x rather than A^T x, same eigenvector but it can't flip
+    /// back and forth on bipartite graphs or die out on dags. when the graph
+    /// falls apart into several components the dominant eigenvector isn't
+    /// unique, so it always starts from the uniform vector to at least make
+    /// the answer deterministic. returns the iterations done
+    pub fn calculate_eigenvector(&mut self, convergence: f64) -> i32 {
+        let start = 1f64 / (self.nodes.len().max(1) as f64).sqrt();
+        for n in self.nodes.iter_mut() {
+            n.score = start;
+        }
+
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| {
+                let next = pr
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(id, n)| old[id] + n.incoming_edges.iter().map(|source| old[*source]).sum::<f64>())
+                    .collect::<Vec<f64>>();
+                let length = norm(&next);
+                next.into_iter().map(|x| x / length).collect()
+            },
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        )
+    }
+
+    /// sum of x over a node's in-neighbors, each copy of an edge weighted
+    /// like it is when score gets distributed
+    fn weighted_incoming(&self, n: &GraphNode<T>, x: &[f64]) -> f64 {
+        n.incoming_edges
+            .iter()
+            .zip(n.incoming_meta.iter())
+            .map(|(source, meta)| x[*source] * self.edge_weight(meta))
+            .sum::<f64>()
+    }
+
+    /// upper bound on the spectral radius of the weighted adjacency katz
+    /// iterates on (counting parallel edges). never below the true radius
+    /// but only slowly tightens on dags, whose radius is 0
+    fn spectral_radius_bound(&self) -> f64 {
+        let mut out_degrees = vec![0f64; self.nodes.len()];
+        let mut max_in = 0f64;
+        for n in self.nodes.iter() {
+            let mut in_degree = 0f64;
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                in_degree += self.edge_weight(meta);
+                out_degrees[*source] += self.edge_weight(meta);
+            }
+            max_in = max_in.max(in_degree);
+        }
+        let max_out = out_degrees.into_iter().fold(0f64, f64::max);
+        let degree_bound = max_in.min(max_out);
+        if degree_bound <= 1f64 {
+            return degree_bound;
+        }
+
+        // power iteration on I + A^T keeps x positive, and for any positive x
+        // the largest ratio ((I + A^T) x)_i / x_i bounds 1 + radius from above
+        // (collatz-wielandt). the ratio only shrinks as x lines up with the
+        // perron vector, so stop once it stops moving. entries off the
+        // dominant component shrink geometrically, stop before any of them
+        // underflows since a zero entry voids the bound
+        const MAX_ROUNDS: usize = 1000;
+        let mut x = vec![1f64; self.nodes.len()];
+        let (mut bound, mut last_ratio) = (degree_bound, f64::INFINITY);
+        for _ in 0..MAX_ROUNDS {
+            let next = self
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(id, n)| x[id] + self.weighted_incoming(n, &x))
+                .collect::<Vec<f64>>();
+            let ratio = next.iter().zip(x.iter()).map(|(after, before)| after / before).fold(0f64, f64::max);
+            bound = bound.min(ratio - 1f64);
+            if last_ratio - ratio <= 1e-12 * ratio {
+                break;
+            }
+            last_ratio = ratio;
+
+            let length = norm(&next);
+            x = next.into_iter().map(|v| v / length).collect();
+            if x.iter().any(|v| *v < f64::MIN_POSITIVE) {
+                break;
+            }
+        }
+        bound.max(0f64)
+    }
+
+    /// How much the ranking moves as damping changes
+    ///
+    /// ranks from scratch with the current damping as the baseline, then
+    /// again with each of dampings, pairing every damping with the kendall
+    /// tau (tau-b, so ties are handled) between its scores and the
+    /// baseline's. 1.0 means the same order. values outside [0, 1) pair with
+    /// NaN. the damping, scores and run stats (step count, snapshots and
+    /// stabilization included) are put back afterwards.
+    /// comparing orders is quadratic in the node count
+    pub fn damping_stability(&mut self, dampings: &[f64], convergence: f64) -> Vec<(f64, f64)> {
+        let (saved, damping) = (self.save_run(), self.damping);
+
+        self.reset_scores();
+        self.calculate_with_convergence(convergence);
+        let baseline = self.scores();
+
+        let stability = dampings
+            .iter()
+            .map(|d| {
+                if !(0f64..1f64).contains(d) {
+                    return (*d, f64::NAN);
+                }
+                self.damping = *d;
+                self.reset_scores();
+                self.calculate_with_convergence(convergence);
+                (*d, kendall_tau(&baseline, &self.scores()))
+            })
+            .collect();
+
+        self.damping = damping;
+        self.restore_run(saved);
+        stability
+    }
+
+    /// Score every node by a blend of how much rank flows into it and how
+    /// much flows out of it: alpha * forward + (1 - alpha) * reverse
+    ///
+    /// the reverse scores come from the same graph with every edge flipped.
+    /// returns the iterations of both runs added up, so the total work rather
+    /// than what either run needed. the run only counts as converged if both
+    /// did, last_residual is the larger of the two and the other run stats
+    /// are the forward run's. panics unless alpha is in 0..=1
+    pub fn calculate_bidirectional(&mut self, alpha: f64, convergence: f64) -> i32 {
+        assert!((0f64..=1f64).contains(&alpha), "alpha {alpha} needs to be between 0 and 1");
+        let mut reverse = self.reversed();
+        let iterations = self.calculate_with_convergence(convergence) + reverse.calculate_with_convergence(convergence);
+        if let Some(reverse_outcome) = reverse.last_outcome.filter(|o| *o != RunOutcome::Converged) {
+            if self.last_outcome == Some(RunOutcome::Converged) {
+                self.last_outcome = Some(reverse_outcome);
+            }
+        }
+        self.last_residual = match (self.last_residual, reverse.last_residual) {
+            (Some(forward), Some(reverse)) => Some(forward.max(reverse)),
+            (forward, reverse) => forward.or(reverse),
+        };
+
+        let blended = self
+            .nodes
+            .iter()
+            .zip(reverse.nodes.iter())
+            .map(|(forward, reverse)| alpha * forward.score + (1f64 - alpha) * reverse.score)
+            .collect::<Vec<f64>>();
+        self.set_scores(&blended);
+        iterations
+    }
+
+    /// calculate pagerank with custom convergence
+    pub fn calculate_with_convergence(&mut self, convergence: f64) -> i32 {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        if !self.auto_stabilize || self.laziness < 1f64 {
+            return self.calculate_with(convergence, |old, new| residual(old, new, with_incoming));
+        }
+
+        // the metric spots the oscillation, the update applies the laziness
+        let lazy = std::cell::Cell::new(1f64);
+        let (mut last_step, mut flips) = (Vec::<f64>::new(), 0);
+        let iterations = self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| {
+                let (p, new) = (lazy.get(), pr.next_scores(old));
+                match p < 1f64 {
+                    true => old.iter().zip(new).map(|(old, new)| (1f64 - p) * old + p * new).collect(),
+                    false => new,
+                }
+            },
+            |old, new| {
+                // a period 2 walk keeps stepping back the way it came
+                let step: Vec<f64> = new.iter().zip(old).map(|(new, old)| new - old).collect();
+                let dot: f64 = step.iter().zip(&last_step).map(|(a, b)| a * b).sum();
+                flips = match dot < 0f64 { true => flips + 1, false => 0 };
+                if flips >= 2 {
+                    lazy.set(AUTO_LAZINESS);
+                }
+                last_step = step;
+                residual(old, new, with_incoming)
+            },
+            |_| true,
+        );
+
+        if lazy.get() < 1f64 {
+            self.laziness = lazy.get();
+        }
+        iterations
+    }
+
+    /// Calulate pagerank with predefined covergence
+    pub fn calculate(&mut self) -> i32 {
+        self.calculate_with_convergence(DEFAULT_CONVERGENCE)
+    }
+
+    /// calculate pagerank until the ranking order stops moving
+    ///
+    /// after every step the spearman correlation between the old and new
+    /// scores gets compared, we stop once it goes above min_correlation. only
+    /// the order matters here so this can stop well before the scores settle
+    pub fn calculate_until_rank_stable(&mut self, min_correlation: f64) -> i32 {
+        self.calculate_with(1f64 - min_correlation, |old, new| 1f64 - spearman(old, new))
+    }
+
+    /// calculate pagerank into a named score column, leaving the main scores
+    /// alone
+    ///
+    /// this runs with whatever settings are active right now and starts from
+    /// the column's previous values (or the default score the first time),
+    /// so several ranking variants can share one graph. read it back with
+    /// get_named_score
+    pub fn calculate_named(&mut self, name: &str, convergence: f64) -> i32 {
+        let main = self.scores();
+        let previous = self.named_scores.remove(name).unwrap_or_default();
+        let baseline = 1f64 - self.damping;
+        let start = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, n)| match n.pinned {
+                true => n.score,
+                false => previous.get(id).copied().unwrap_or(baseline),
+            })
+            .collect::<Vec<f64>>();
+
+        self.set_scores(&start);
+        let iterations = self.calculate_with_convergence(convergence);
+        self.named_scores.insert(name.to_string(), self.scores());
+        self.set_scores(&main);
+        iterations
+    }
+
+    /// Read a node's score from a column filled by calculate_named
+    pub fn get_named_score(&self, name: &str, node: &T) -> Option<f64> {
+        let id = self.node_positions.get(node)?;
+        self.named_scores.get(name)?.get(*id).copied()
+    }
+
+    /// calculate pagerank within a wall clock budget
+    ///
+    /// the clock is checked between iterations so the last one can run a
+    /// little over. stops early if the default convergence is reached first,
+    /// otherwise the run counts as truncated. returns the iterations done
+    pub fn calculate_with_deadline(&mut self, deadline: Duration) -> i32 {
+        let start = Instant::now();
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        self.iterate(
+            DEFAULT_CONVERGENCE,
+            |old, new| residual(old, new, with_incoming),
+            |_| start.elapsed() < deadline,
+        )
+    }
+
+    /// calculate pagerank within a budget of node updates (steps times node
+    /// count) instead of a step count, so bigger graphs get fewer steps
+    ///
+    /// a step only starts if all of it fits in what's left of the budget,
+    /// running out counts as truncated. the iteration cap still applies.
+    /// returns the iterations done
+    pub fn calculate_bounded(&mut self, convergence: f64, max_node_iters: u64) -> i32 {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        let per_step = self.nodes.len() as u64;
+        self.iterate(
+            convergence,
+            |old, new| residual(old, new, with_incoming),
+            |iterations| (iterations as u64 + 1).saturating_mul(per_step) <= max_node_iters,
+        )
+    }
+
+    /// Add an edge and refresh the scores a little, for live rankings that
+    /// can stand being slightly stale
+    ///
+    /// runs at most max_steps iterations from the current scores, stopping
+    /// early at the default convergence, and hands back the ranking like
+    /// nodes() (take as many off the front as the view needs)
+    pub fn observe_edge(&mut self, source: T, target: T, max_steps: usize) -> Vec<(&T, f64)> {
+        self.add_edge(source, target);
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        self.iterate(
+            DEFAULT_CONVERGENCE,
+            |old, new| residual(old, new, with_incoming),
+            |iterations| (iterations as usize) < max_steps,
+        );
+        self.nodes()
+    }
+
+    /// calculate pagerank with custom convergence, recording the residual and
+    /// wall clock time of every iteration
+    pub fn calculate_with_timing(&mut self, convergence: f64) -> Vec<(f64, Duration)> {
+        let with_incoming = self.len_nodes_with_incoming_edges();
+        let mut timings = Vec::new();
+        let mut started = Instant::now();
+
+        self.calculate_with(convergence, |old, new| {
+            let residual = residual(old, new, with_incoming);
+            timings.push((residual, started.elapsed()));
+            started = Instant::now();
+            residual
+        });
+
+        timings
+    }
+
+    /// Residual of the most recent step, None before the first one
+    pub fn last_residual(&self) -> Option<f64> {
+        self.last_residual
+    }
+    
+    /// Approximate the score of a few target nodes without a global run
+    ///
+    /// this runs a reverse local push (Andersen et al.) back from each target
+    /// over the incoming edges, so only the neighborhood that actually feeds
+    /// rank into it gets explored. residuals at or below convergence are left
+    /// unpushed, the smaller it is the closer we get to what calculate would
+    /// settle on. targets that aren't in the graph are skipped.
+    ///
+    /// only damping, edge weights and the teleport vector (personalization
+    /// and exclusions) make it into the push. it ignores dangling handling
+    /// (Teleport and dangling sinks alike, dangling rank just leaks), absorbing
+    /// nodes, pins, per-node damping, laziness, forced restarts and the
+    /// incoming edge cap, so with any of those set expect it to drift from
+    /// calculate
+    pub fn local_rank(&self, targets: &[T], convergence: f64) -> HashMap<&T, f64> {
+        let teleport = self.teleport();
+
+        targets
+            .iter()
+            .filter_map(|target| self.node_positions.get(target))
+            .map(|id| (&self.nodes[*id].node, self.reverse_push(*id, convergence, &teleport)))
+            .collect()
+    }
+
+    /// estimate one node's score by pushing residual mass back along the
+    /// incoming edges until nothing above epsilon is left
+    fn reverse_push(&self, target: usize, epsilon: f64, teleport: &[f64]) -> f64 {
+        // estimates[u] approximates how much of target's score comes from
+        // teleporting to u
+        let mut estimates = HashMap::<usize, f64>::new();
+        let mut residuals = HashMap::<usize, f64>::from([(target, 1f64)]);
+        let mut queue = VecDeque::from([target]);
+        let out_weights = self.out_weights();
+
+        while let Some(id) = queue.pop_front() {
+            let residual = residuals.insert(id, 0f64).unwrap_or(0f64);
+            *estimates.entry(id).or_default() += (1f64 - self.damping) * residual;
+
+            let n = &self.nodes[id];
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                let share = self.edge_share(*source, meta, &out_weights);
+                let pending = residuals.entry(*source).or_default();
+                let before = *pending;
+                *pending += self.damping * residual * share;
+                if before <= epsilon && *pending > epsilon {
+                    queue.push_back(*source);
+                }
+            }
+        }
+
+        estimates
+            .iter()
+            .map(|(id, estimate)| teleport[*id] * estimate)
+            .sum()
+    }
+
+    /// Find every simple directed path from one node to another that uses at
+    /// most max_len edges
+    ///
+    /// this is a plain dfs so it blows up fast on dense graphs, keep max_len
+    /// small. parallel edges don't produce duplicate paths and a node is
+    /// reached from itself by the single node path
+    pub fn simple_paths(&self, from: T, to: T, max_len: usize) -> Vec<Vec<&T>> {
+        let (Some(from), Some(to)) = (self.node_positions.get(&from), self.node_positions.get(&to)) else {
+            return Vec::new();
+        };
+
+        let adjacency = self.outgoing_adjacency();
+        let mut paths = Vec::new();
+        let mut path = vec![*from];
+        let mut on_path = vec![false; self.nodes.len()];
+        on_path[*from] = true;
+        self.extend_paths(&adjacency, *to, max_len, &mut path, &mut on_path, &mut paths);
+
+        paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|id| &self.nodes[id].node).collect())
+            .collect()
+    }
+
+    /// dfs step for simple_paths, path always ends in the node being expanded
+    fn extend_paths(
+        &self,
+        adjacency: &[Vec<usize>],
+        to: usize,
+        max_len: usize,
+        path: &mut Vec<usize>,
+        on_path: &mut [bool],
+        paths: &mut Vec<Vec<usize>>,
+    ) {
+        let last = path[path.len() - 1];
+        if last == to {
+            paths.push(path.clone());
+            return;
+        }
+        if path.len() > max_len {
+            return;
+        }
+
+        for next in adjacency[last].iter() {
+            if on_path[*next] {
+                continue;
+            }
+            on_path[*next] = true;
+            path.push(*next);
+            self.extend_paths(adjacency, to, max_len, path, on_path, paths);
+            path.pop();
+            on_path[*next] = false;
+        }
+    }
+
+    /// distinct targets of every node, parallel edges collapsed
+    fn outgoing_adjacency(&self) -> Vec<Vec<usize>> {
+        self.nodes
+            .iter()
+            .map(|n| {
+                let mut targets = n.outgoing_edges.clone();
+                targets.sort_unstable();
+                targets.dedup();
+                targets
+            })
+            .collect()
+    }
+
+    /// Edges whose removal leaves the graph acyclic, picked greedily
+    ///
+    /// uses the Eades, Lin and Smyth heuristic: keep peeling off sinks (to
+    /// the back of an ordering) and sources (to the front), and when neither
+    /// is left move the node with the most out minus in edges to the front.
+    /// every edge pointing backwards in that order is returned. not minimal
+    /// in general, self loops are always in and parallel edges appear once
+    /// per copy
+    pub fn feedback_arc_set(&self) -> Vec<(&T, &T)> {
+        let n = self.nodes.len();
+        // degrees among the nodes still in play, self loops don't count
+        let mut in_degree = vec![0usize; n];
+        let mut out_degree = vec![0usize; n];
+        for (source, target) in self.edge_ids().filter(|(source, target)| source != target) {
+            out_degree[source] += 1;
+            in_degree[target] += 1;
+        }
+
+        let mut sinks = (0..n).rev().filter(|id| out_degree[*id] == 0).collect::<Vec<usize>>();
+        let mut sources = (0..n).rev().filter(|id| in_degree[*id] == 0).collect::<Vec<usize>>();
+        let mut removed = vec![false; n];
+        let mut front = Vec::with_capacity(n);
+        let mut back = Vec::new();
+
+        loop {
+            // degrees only ever go down so a stacked node stays a sink/source
+            let id = if let Some(id) = sinks.pop() {
+                if removed[id] {
+                    continue;
+                }
+                back.push(id);
+                id
+            } else if let Some(id) = sources.pop() {
+                if removed[id] {
+                    continue;
+                }
+                front.push(id);
+                id
+            } else {
+                let Some(id) = (0..n)
+                    .filter(|id| !removed[*id])
+                    .min_by_key(|id| in_degree[*id] as isize - out_degree[*id] as isize)
+                else {
+                    break;
+                };
+                front.push(id);
+                id
+            };
+
+            removed[id] = true;
+            for target in self.nodes[id].outgoing_edges.iter().filter(|target| **target != id) {
+                in_degree[*target] -= 1;
+                if in_degree[*target] == 0 && !removed[*target] {
+                    sources.push(*target);
+                }
+            }
+            for source in self.nodes[id].incoming_edges.iter().filter(|source| **source != id) {
+                out_degree[*source] -= 1;
+                if out_degree[*source] == 0 && !removed[*source] {
+                    sinks.push(*source);
+                }
+            }
+        }
+
+        let mut position = vec![0usize; n];
+        for (i, id) in front.into_iter().chain(back.into_iter().rev()).enumerate() {
+            position[id] = i;
+        }
+        self.edge_ids()
+            .filter(|(source, target)| position[*source] >= position[*target])
+            .map(|(source, target)| (&self.nodes[source].node, &self.nodes[target].node))
+            .collect()
+    }
+
+    /// Harmonic centrality of every node, the sum of 1 / d(u, v) over every
+    /// other node u that can reach it (d counted in hops along out edges)
+    ///
+    /// exact with None, which is a bfs per node. with Some(k) only k random
+    /// sources (picked by seed, so a seed always gives the same answer) are
+    /// used and the sums get scaled by n / k, an unbiased estimate for graphs
+    /// too big to do exactly. not normalized by n - 1
+    pub fn calculate_harmonic_centrality(&self, sample_sources: Option<usize>, seed: u64) -> HashMap<&T, f64> {
+        let n = self.nodes.len();
+        let sources = match sample_sources {
+            Some(k) if k < n => SplitMix64(seed).sample(n, k),
+            _ => (0..n).collect(),
+        };
+        let scale = match sources.is_empty() {
+            true => 0f64,
+            false => n as f64 / sources.len() as f64,
+        };
+
+        let mut centrality = vec![0f64; n];
+        for source in sources {
+            for (target, distance) in self.distances_from(source).into_iter().enumerate() {
+                if let Some(distance) = distance.filter(|d| *d > 0) {
+                    centrality[target] += scale / distance as f64;
+                }
+            }
+        }
+        self.nodes.iter().zip(centrality).map(|(n, c)| (&n.node, c)).collect()
+    }
+
+    /// Estimate the diameter as the longest shortest path seen from a random
+    /// sample of source nodes
+    ///
+    /// each sample is a bfs over the out edges, unreachable pairs are ignored.
+    /// this is a lower bound that gets exact once samples covers every node.
+    /// the same seed always picks the same sources
+    pub fn estimate_diameter(&self, samples: usize, seed: u64) -> usize {
+        SplitMix64(seed)
+            .sample(self.nodes.len(), samples)
+            .into_iter()
+            .map(|source| {
+                self.distances_from(source)
+                    .into_iter()
+                    .flatten()
+                    .max()
+                    .unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Count the nodes reachable from a node by following out edges
+    ///
+    /// the source itself isn't counted unless a cycle leads back to it, so a
+    /// node with no out edges reaches 0. None if the node doesn't exist
+    pub fn reachable_count(&self, from: T) -> Option<usize> {
+        let source = *self.node_positions.get(&from)?;
+        let distances = self.distances_from(source);
+        let back_to_source = self
+            .nodes
+            .iter()
+            .enumerate()
+            .any(|(id, n)| distances[id].is_some() && n.outgoing_edges.contains(&source));
+
+        let reached = distances.iter().filter(|d| d.is_some()).count();
+        Some(reached - 1 + back_to_source as usize)
+    }
+
+    /// bfs hop counts from one node over the out edges, None if unreachable
+    fn distances_from(&self, source: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.nodes.len()];
+        distances[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(id) = queue.pop_front() {
+            let next = distances[id].map(|d| d + 1);
+            for target in self.nodes[id].outgoing_edges.iter() {
+                if distances[*target].is_none() {
+                    distances[*target] = next;
+                    queue.push_back(*target);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Calculate pagerank reading the edges from source on every pass
+    ///
+    /// only the node values, scores and out-degree counts stay resident, the
+    /// edges themselves are never stored. a first pass registers the nodes and
+    /// counts degrees, after that each iteration is one more pass. edges
+    /// already stored in the graph are ignored here and updates are always
+    /// jacobi style. nodes seen in the source are added even past the node
+    /// cap
+    pub fn calculate_streaming<S: EdgeSource<T>>(&mut self, source: &S, convergence: f64) -> i32 {
+        let mut out_degrees = vec![0usize; self.nodes.len()];
+        let mut has_incoming = vec![false; self.nodes.len()];
+        for (from, to) in source.edges() {
+            let from = self.get_or_push_node(from);
+            let to = self.get_or_push_node(to);
+            out_degrees.resize(self.nodes.len(), 0);
+            has_incoming.resize(self.nodes.len(), false);
+            out_degrees[from] += 1;
+            has_incoming[to] = true;
+        }
+        let with_incoming = has_incoming.iter().filter(|x| **x).count();
+
+        self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| pr.streamed_scores(source, &out_degrees, old),
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        )
+    }
+
+    /// one jacobi sweep where the incoming contributions come from a pass
+    /// over the edge source
+    fn streamed_scores<S: EdgeSource<T>>(&self, source: &S, out_degrees: &[usize], old: &[f64]) -> Vec<f64> {
+        let mut incoming = vec![0f64; self.nodes.len()];
+        for (from, to) in source.edges() {
+            if let (Some(from), Some(to)) = (self.node_positions.get(&from), self.node_positions.get(&to)) {
+                if !self.nodes[*from].absorbing {
+                    incoming[*to] += old[*from] / out_degrees[*from] as f64;
+                }
+            }
+        }
+
+        let teleport = self.teleport();
+        let dangling = self.dangling_shares(old, &teleport, |id| out_degrees[id] == 0 && !self.nodes[id].absorbing);
+        let restart = self.restart_due();
+        (0..self.nodes.len())
+            .map(|id| self.updated_score(id, old, incoming[id] + dangling[id], teleport[id], restart))
+            .collect()
+    }
+
+    /// Calculate pagerank over self and others as one graph, without merging
+    /// anything
+    ///
+    /// the union keeps every edge of every graph, so an edge present in two
+    /// of them counts twice. nodes that only exist in others get added to
+    /// self (edgeless) so they can hold a score, the other graphs' edges are
+    /// never copied. settings like damping, personalization and pins come
+    /// from self and updates are always jacobi style. the added nodes don't
+    /// count against the node cap
+    pub fn calculate_over<'a, I>(&mut self, others: I, convergence: f64) -> i32
+    where
+        I: IntoIterator<Item = &'a Pagerank<T>>,
+        T: 'a,
+    {
+        let others = others.into_iter().collect::<Vec<&Pagerank<T>>>();
+        for other in others.iter() {
+            for n in other.nodes.iter() {
+                self.get_or_push_node(n.node.clone());
+            }
+        }
+        // every other graph's ids translated into ours
+        let mappings = others
+            .iter()
+            .map(|other| other.nodes.iter().map(|n| self.node_positions[&n.node]).collect())
+            .collect::<Vec<Vec<usize>>>();
+
+        let mut out_weights = self.out_weights();
+        let mut has_incoming = self.nodes.iter().map(|n| !n.incoming_edges.is_empty()).collect::<Vec<bool>>();
+        for (other, mapping) in others.iter().zip(mappings.iter()) {
+            for (target, n) in other.nodes.iter().enumerate() {
+                for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                    out_weights[mapping[*source]] += other.edge_weight(meta);
+                    has_incoming[mapping[target]] = true;
+                }
+            }
+        }
+        let with_incoming = has_incoming.iter().filter(|x| **x).count();
+
+        self.iterate_by(
+            convergence,
+            |pr: &Self, old: &[f64]| pr.union_scores(&others, &mappings, &out_weights, old),
+            |old, new| residual(old, new, with_incoming),
+            |_| true,
+        )
+    }
+
+    /// one jacobi sweep over the union of self and others
+    fn union_scores(
+        &self,
+        others: &[&Pagerank<T>],
+        mappings: &[Vec<usize>],
+        out_weights: &[f64],
+        old: &[f64],
+    ) -> Vec<f64> {
+        let mut incoming = vec![0f64; self.nodes.len()];
+        let graphs = std::iter::once((self, None))
+            .chain(others.iter().zip(mappings.iter()).map(|(graph, mapping)| (*graph, Some(mapping))));
+        for (graph, mapping) in graphs {
+            let ours = |id: usize| mapping.map_or(id, |mapping| mapping[id]);
+            for (target, n) in graph.nodes.iter().enumerate() {
+                for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                    let source = ours(*source);
+                    if out_weights[source] > 0f64 && !self.nodes[source].absorbing {
+                        incoming[ours(target)] += old[source] * graph.edge_weight(meta) / out_weights[source];
+                    }
+                }
+            }
+        }
+
+        let teleport = self.teleport();
+        let dangling = self.dangling_shares(old, &teleport, |id| out_weights[id] <= 0f64 && !self.nodes[id].absorbing);
+        let restart = self.restart_due();
+        (0..self.nodes.len())
+            .map(|id| self.updated_score(id, old, incoming[id] + dangling[id], teleport[id], restart))
+            .collect()
+    }
+
+    /// Get count of nodes in graph
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return nodes sorted by pagerank
+    /// scores within the sort epsilon of each other count as tied and tied
+    /// nodes come out in insertion order
+    pub fn nodes(&self) -> Vec<(&T, f64)> {
+        self.ranked_ids()
+            .into_iter()
+            .map(|id| (&self.nodes[id].node, self.nodes[id].score))
+            .collect()
+    }
+
+    /// One record per node with its score and degrees, in insertion order
+    pub fn node_records(&self) -> Vec<NodeRecord<&T>> {
+        self.nodes
+            .iter()
+            .map(|n| NodeRecord {
+                node: &n.node,
+                score: n.score,
+                in_degree: n.incoming_edges.len(),
+                out_degree: n.outgoing_edges.len(),
+            })
+            .collect()
+    }
+
+    /// The node nodes() would put first, found with linear scans instead of
+    /// a sort
+    ///
+    /// ties (within the sort epsilon) go to the earliest inserted node. None
+    /// on an empty graph
+    pub fn top_node(&self) -> Option<(&T, f64)> {
+        let top = self.nodes.iter().map(|n| n.score).reduce(f64::max)?;
+        self.nodes
+            .iter()
+            .find(|n| top - n.score <= self.sort_epsilon)
+            .map(|n| (&n.node, n.score))
+    }
+
+    /// The top n nodes of every category, each list in nodes() order
+    ///
+    /// categories only show up if they have at least one node, n = 0 keeps
+    /// the categories with empty lists
+    pub fn top_k_by_category<K, F>(&self, n: usize, category_fn: F) -> HashMap<K, Vec<(&T, f64)>>
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        let mut top = HashMap::<K, Vec<(&T, f64)>>::new();
+        for id in self.ranked_ids() {
+            let node = &self.nodes[id];
+            let entries = top.entry(category_fn(&node.node)).or_default();
+            if entries.len() < n {
+                entries.push((&node.node, node.score));
+            }
+        }
+        top
+    }
+
+    /// Rank flowing between groups of nodes in a step, keyed by (source
+    /// group, target group)
+    ///
+    /// every edge adds what score_breakdown credits it with to its group
+    /// pair, flow within a group lands on (group, group), including the
+    /// score absorbing nodes hold on to. pairs without any flow are left out
+    pub fn group_flow<K, F>(&self, group_fn: F) -> HashMap<(K, K), f64>
+    where
+        K: Eq + Hash + Clone,
+        F: Fn(&T) -> K,
+    {
+        let groups = self.nodes.iter().map(|n| group_fn(&n.node)).collect::<Vec<K>>();
+        let out_weights = self.flow_out_weights();
+        let mut flow = HashMap::new();
+        for (target, n) in self.nodes.iter().enumerate() {
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()).take(self.max_incoming) {
+                if !self.nodes[*source].absorbing {
+                    *flow.entry((groups[*source].clone(), groups[target].clone())).or_insert(0f64) +=
+                        self.edge_flow(*source, target, meta, &out_weights);
+                }
+            }
+            if n.absorbing {
+                *flow.entry((groups[target].clone(), groups[target].clone())).or_insert(0f64) +=
+                    n.damping.unwrap_or(self.damping) * n.score;
+            }
+        }
+        flow
+    }
+
+    /// Same order as nodes but hands back the node values themselves,
+    /// consuming the graph so nothing gets cloned
+    pub fn into_nodes(self) -> Vec<(T, f64)> {
+        let order = self.ranked_ids();
+        let mut nodes = self.nodes.into_iter().map(Some).collect::<Vec<Option<GraphNode<T>>>>();
+
+        order
+            .into_iter()
+            .filter_map(|id| nodes[id].take())
+            .map(|n| (n.node, n.score))
+            .collect()
+    }
+
+    /// ids sorted by score, highest first, see nodes
+    fn ranked_ids(&self) -> Vec<usize> {
+        let mut ids = (0..self.nodes.len()).collect::<Vec<usize>>();
+        // stable, so exact ties are already in insertion order
+        ids.sort_by(|a, b| self.nodes[*b].score.partial_cmp(&self.nodes[*a].score).unwrap());
+
+        if self.sort_epsilon > 0f64 {
+            // put each tied group back in id order. grouping after the fact
+            // keeps the sort itself a total order
+            for run in self.tied_runs(&ids, self.sort_epsilon) {
+                ids[run].sort_unstable();
+            }
+        }
+
+        ids
+    }
+
+    /// runs of ids (sorted by score, highest first) whose scores are all
+    /// within eps of the run's top score
+    fn tied_runs(&self, ids: &[usize], eps: f64) -> Vec<std::ops::Range<usize>> {
+        let mut runs = Vec::new();
+        let mut start = 0;
+        while start < ids.len() {
+            let top = self.nodes[ids[start]].score;
+            let mut end = start + 1;
+            while end < ids.len() && top - self.nodes[ids[end]].score <= eps {
+                end += 1;
+            }
+            runs.push(start..end);
+            start = end;
+        }
+        runs
+    }
+
+    /// 1-based rank of every node in the nodes() order
+    ///
+    /// nodes tied within the sort epsilon (exactly equal by default) all get
+    /// the best rank of their group and the next group skips past them, so
+    /// two nodes tied for first are both 1 and the next one is 3
+    pub fn ordinal_ranks(&self) -> HashMap<&T, usize> {
+        let ids = self.ranked_ids();
+        let mut ranks = HashMap::with_capacity(ids.len());
+        for run in self.tied_runs(&ids, self.sort_epsilon) {
+            let rank = run.start + 1;
+            for id in ids[run].iter() {
+                ranks.insert(&self.nodes[*id].node, rank);
+            }
+        }
+        ranks
+    }
+
+    /// Nodes sorted like nodes() but bucketed by rank, where every score
+    /// within epsilon of the bucket's top score shares the bucket
+    ///
+    /// with competition ranking a bucket's rank is one plus the size of all
+    /// the buckets before it. nodes inside a bucket are in insertion order
+    pub fn ranked_groups(&self, epsilon: f64) -> Vec<Vec<(&T, f64)>> {
+        let mut ids = (0..self.nodes.len()).collect::<Vec<usize>>();
+        ids.sort_by(|a, b| self.nodes[*b].score.partial_cmp(&self.nodes[*a].score).unwrap());
+
+        self.tied_runs(&ids, epsilon.max(0f64))
+            .into_iter()
+            .map(|run| {
+                let mut group = ids[run].to_vec();
+                group.sort_unstable();
+                group.into_iter().map(|id| (&self.nodes[id].node, self.nodes[id].score)).collect()
+            })
+            .collect()
+    }
+
+    /// Rank mass missing from the current scores, the node count (what the
+    /// scores sum to when nothing leaks) minus their actual total
+    ///
+    /// after a converged run this is what dangling nodes lose every step.
+    /// divide by len() for the leaked fraction. can dip below 0 when pinned or
+    /// transformed scores add up to more than n
+    pub fn leaked_mass(&self) -> f64 {
+        self.nodes.len() as f64 - self.nodes.iter().map(|n| n.score).sum::<f64>()
+    }
+
+    /// Shannon entropy (in bits) of the scores normalized to a distribution
+    ///
+    /// log2(n) for a perfectly uniform ranking, close to 0 when one node holds
+    /// all the mass. empty graphs and all zero scores give 0.0
+    pub fn score_entropy(&self) -> f64 {
+        let total = self.nodes.iter().map(|n| n.score).sum::<f64>();
+        if total <= 0f64 {
+            return 0f64;
+        }
+
+        -self
+            .nodes
+            .iter()
+            .map(|n| n.score / total)
+            .filter(|p| *p > 0f64)
+            .map(|p| p * p.log2())
+            .sum::<f64>()
+    }
+
+    /// Gini coefficient of the scores, 0 when every node scores the same and
+    /// heading to 1 as one node takes everything. empty graphs and all zero
+    /// scores give 0.0
+    pub fn score_gini(&self) -> f64 {
+        let mut scores = self.scores();
+        let total = scores.iter().sum::<f64>();
+        if scores.is_empty() || total <= 0f64 {
+            return 0f64;
+        }
+
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = scores.len() as f64;
+        let weighted = scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| (i + 1) as f64 * score)
+            .sum::<f64>();
+
+        // rounding can dip just under 0 for equal scores
+        (2f64 * weighted / (n * total) - (n + 1f64) / n).clamp(0f64, 1f64)
+    }
+
+    /// Get count of edges in graph
+    pub fn len_node(&self) -> usize {
+        self.edges
+    }
+
+    /// Edge density, edges over the n * (n - 1) possible directed edges
+    ///
+    /// self loops and parallel edges still count as edges so this can go
+    /// above 1.0 on multigraphs. graphs with fewer than two nodes are 0.0
+    pub fn density(&self) -> f64 {
+        let n = self.nodes.len();
+        if n < 2 {
+            return 0f64;
+        }
+
+        self.edges as f64 / (n * (n - 1)) as f64
+    }
+
+    /// Degree assortativity, the pearson correlation between the out-degree of
+    /// each edge's source and the in-degree of its target
+    ///
+    /// positive when busy nodes link to other busy nodes. graphs without edges
+    /// or where either degree is the same on every edge give 0.0
+    pub fn degree_assortativity(&self) -> f64 {
+        let pairs = self
+            .nodes
+            .iter()
+            .flat_map(|n| {
+                n.incoming_edges
+                    .iter()
+                    .map(|source| (self.nodes[*source].outgoing_edges.len() as f64, n.incoming_edges.len() as f64))
+            })
+            .collect::<Vec<(f64, f64)>>();
+        if pairs.is_empty() {
+            return 0f64;
+        }
+
+        let (x, y): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+        pearson(&x, &y).unwrap_or(0f64)
+    }
+
+    /// Do both graphs have the same nodes and edges, with every score
+    /// within score_tol of its counterpart? ids don't need to line up
+    pub fn approx_eq(&self, other: &Pagerank<T>, score_tol: f64) -> bool {
+        if self.nodes.len() != other.nodes.len() || self.edges != other.edges {
+            return false;
+        }
+
+        self.nodes.iter().all(|n| {
+            let Some(id) = other.node_positions.get(&n.node) else {
+                return false;
+            };
+            let o = &other.nodes[*id];
+            let difference = (n.score - o.score).abs();
+            if difference.is_nan()
+                || score_tol.is_nan()
+                || difference > score_tol
+                || n.outgoing_edges.len() != o.outgoing_edges.len()
+                || n.incoming_edges.len() != o.incoming_edges.len()
+            {
+                return false;
+            }
+
+            // incoming sources have to match as a multiset
+            let mut counts = HashMap::<&T, i64>::new();
+            for source in n.incoming_edges.iter() {
+                *counts.entry(&self.nodes[*source].node).or_default() += 1;
+            }
+            for source in o.incoming_edges.iter() {
+                *counts.entry(&other.nodes[*source].node).or_default() -= 1;
+            }
+            counts.values().all(|count| *count == 0)
+        })
+    }
+
+    /// Is the graph empty?
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+}
+
+impl<T> Pagerank<T>
+where
+    T: Eq + Hash + Clone + Ord,
+{
+    /// nodes() with ties broken by node value instead of insertion order,
+    /// so graphs built in a different order still list their nodes the same
+    /// way. ties are the same as in nodes(), exact or within the sort epsilon
+    pub fn nodes_sorted_by_value(&self) -> Vec<(&T, f64)> {
+        let mut ids = self.ranked_ids();
+        for run in self.tied_runs(&ids, self.sort_epsilon) {
+            ids[run].sort_by(|a, b| self.nodes[*a].node.cmp(&self.nodes[*b].node));
+        }
+        ids.into_iter()
+            .map(|id| (&self.nodes[id].node, self.nodes[id].score))
+            .collect()
+    }
+}
+
+impl<T> Pagerank<T>
+where
+    T: Eq + Hash + Clone + Display,
+{
+    /// Stream every edge to w as "source<delimiter>target" lines
+    ///
+    /// edges are written as we walk the graph so nothing gets collected up front
+    pub fn write_edge_list<W: Write>(&self, mut w: W, delimiter: char) -> io::Result<()> {
+        for target in self.nodes.iter() {
+            for source in target.incoming_edges.iter() {
+                writeln!(w, "{}{}{}", self.nodes[*source].node, delimiter, target.node)?;
+            }
+        }
+        w.flush()
+    }
+
+    /// Write a "node,score" header then one row per node, highest score first
+    pub fn write_scores_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "node,score")?;
+        for (node, score) in self.nodes() {
+            writeln!(w, "{},{}", csv_escape(&node.to_string()), score)?;
+        }
+        w.flush()
+    }
+
+    /// Dump nodes and edges in the shape d3/vis.js style viewers expect:
+    /// {"nodes":[{"id","score"}],"edges":[{"source","target","weight"}]}
+    ///
+    /// ids are the Display strings, nodes come in insertion order and edge
+    /// weights are the ones used when ranking. non-finite numbers become null
+    pub fn to_vis_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|n| format!("{{\"id\":{},\"score\":{}}}", json_string(&n.node.to_string()), json_number(n.score)))
+            .collect::<Vec<String>>();
+        let edges = self
+            .nodes
+            .iter()
+            .flat_map(|n| {
+                n.incoming_edges.iter().zip(n.incoming_meta.iter()).map(move |(source, meta)| {
+                    format!(
+                        "{{\"source\":{},\"target\":{},\"weight\":{}}}",
+                        json_string(&self.nodes[*source].node.to_string()),
+                        json_string(&n.node.to_string()),
+                        json_number(self.edge_weight(meta))
+                    )
+                })
+            })
+            .collect::<Vec<String>>();
+
+        format!("{{\"nodes\":[{}],\"edges\":[{}]}}", nodes.join(","), edges.join(","))
+    }
+}
+
+/// quote a string for json, escaping quotes, backslashes and control chars
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// a json number, json has no nan or infinity so those are null
+fn json_number(value: f64) -> String {
+    match value.is_finite() {
+        true => value.to_string(),
+        false => "null".to_string(),
+    }
+}
+
+/// quote a csv field if it needs it, doubling up any quotes inside
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(feature = "petgraph")]
+impl<T> Pagerank<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Copy the graph into a petgraph Graph, node weights are the node
+    /// values and edge weights are the weights used when ranking (1.0 for
+    /// plain edges)
+    pub fn to_petgraph(&self) -> petgraph::Graph<T, f64> {
+        let mut graph = petgraph::Graph::with_capacity(self.nodes.len(), self.edges);
+        let indices = self
+            .nodes
+            .iter()
+            .map(|n| graph.add_node(n.node.clone()))
+            .collect::<Vec<petgraph::graph::NodeIndex>>();
+
+        for (target, n) in self.nodes.iter().enumerate() {
+            for (source, meta) in n.incoming_edges.iter().zip(n.incoming_meta.iter()) {
+                graph.add_edge(indices[*source], indices[target], self.edge_weight(meta));
+            }
+        }
+
+        graph
+    }
+
+    /// Build a graph from an unweighted petgraph Graph
+    ///
+    /// nodes keep petgraph's index order, so isolated nodes survive too
+    pub fn from_petgraph(g: &petgraph::Graph<T, ()>) -> Pagerank<T> {
+        let mut pagerank = Pagerank::new();
+        pagerank.register_nodes(g.node_weights().cloned());
+
+        for edge in g.raw_edges() {
+            pagerank.add_edge(g[edge.source()].clone(), g[edge.target()].clone());
+        }
+
+        pagerank
+    }
+}
+
+/// pearson correlation of two equally long samples, None when either one
+/// has no variance
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let count = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / count;
+    let mean_y = y.iter().sum::<f64>() / count;
+    let (mut cov, mut var_x, mut var_y) = (0f64, 0f64, 0f64);
+    for (x, y) in x.iter().zip(y.iter()) {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x) * (x - mean_x);
+        var_y += (y - mean_y) * (y - mean_y);
+    }
+
+    if var_x == 0f64 || var_y == 0f64 {
+        return None;
+    }
+    Some(cov / (var_x.sqrt() * var_y.sqrt()))
+}
+
+/// rank of every value (0 based), ties share the average of their ranks
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order = (0..values.len()).collect::<Vec<usize>>();
+    order.sort_by(|a, b| values[*a].partial_cmp(&values[*b]).unwrap());
+
+    let mut ranks = vec![0f64; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end) as f64 / 2f64;
+        for id in order[start..=end].iter() {
+            ranks[*id] = rank;
+        }
+        start = end + 1;
+    }
+    ranks
+}
+
+/// spearman rank correlation, two orderings that are both completely tied
+/// count as identical
+fn spearman(a: &[f64], b: &[f64]) -> f64 {
+    let (a, b) = (ranks(a), ranks(b));
+    match pearson(&a, &b) {
+        Some(correlation) => correlation,
+        None if a == b => 1f64,
+        None => 0f64,
+    }
+}
+
+/// kendall tau-b between two score vectors, two orderings that are both
+/// completely tied count as identical
+fn kendall_tau(a: &[f64], b: &[f64]) -> f64 {
+    let (mut concordant, mut discordant) = (0f64, 0f64);
+    let (mut tied_a, mut tied_b) = (0f64, 0f64);
+    for i in 0..a.len() {
+        for j in i + 1..a.len() {
+            let (x, y) = (a[i] - a[j], b[i] - b[j]);
+            if x == 0f64 {
+                tied_a += 1f64;
+            }
+            if y == 0f64 {
+                tied_b += 1f64;
+            }
+            match (x * y).partial_cmp(&0f64) {
+                Some(std::cmp::Ordering::Greater) => concordant += 1f64,
+                Some(std::cmp::Ordering::Less) => discordant += 1f64,
+                _ => {}
+            }
+        }
+    }
+
+    let pairs = (a.len() * a.len().saturating_sub(1) / 2) as f64;
+    let denominator = ((pairs - tied_a) * (pairs - tied_b)).sqrt();
+    match denominator > 0f64 {
+        true => (concordant - discordant) / denominator,
+        false if tied_a == tied_b => 1f64,
+        false => 0f64,
+    }
+}
+
+/// everything a calculate leaves behind apart from the graph, so probing
+/// runs can put it back
+struct RunState {
+    scores: Vec<f64>,
+    stabilized_at: Vec<Option<u32>>,
+    outcome: Option<RunOutcome>,
+    residual: Option<f64>,
+    steps_taken: u64,
+    laziness: f64, // auto stabilize may have switched it on
+    snapshots: HashMap<u32, Vec<f64>>,
+}
+
+/// tiny splitmix64 generator, enough for sampling without pulling in a crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniform-ish index below n, n has to be above 0
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// k distinct indices below n (all of them if k >= n)
+    fn sample(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut ids = (0..n).collect::<Vec<usize>>();
+        let k = k.min(n);
+        for i in 0..k {
+            let j = i + self.below(n - i);
+            ids.swap(i, j);
+        }
+        ids.truncate(k);
+        ids
+    }
+}
+
+/// l2 norm of a vector
+fn norm(values: &[f64]) -> f64 {
+    values.iter().map(|v| v * v).sum::<f64>().sqrt()
+}
+
+/// default residual, the l2 norm of the change spread over the nodes that
+/// actually receive rank
+fn residual(old: &[f64], new: &[f64], with_incoming: usize) -> f64 {
+    let convergence: f64 = old
+        .iter()
+        .zip(new.iter())
+        .map(|(a, b)| {
+            let diff = a - b;
+            diff * diff
+        })
+        .sum();
+
+    // an edgeless graph would divide 0 by 0 and never converge
+    convergence.sqrt() / with_incoming.max(1) as f64
+}
+
+/// A node picked out with Pagerank::node_entry, created if it wasn't there
+pub struct NodeEntry<'a, T>
+where
+    T: Eq + Hash + Clone,
+{
+    graph: &'a mut Pagerank<T>,
+    id: usize,
+    inserted: bool,
+}
+
+impl<'a, T> NodeEntry<'a, T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Set the starting score, but only if the node was just created
+    pub fn or_insert_score(mut self, score: f64) -> Self {
+        if self.inserted {
+            self.set_score(score);
+        }
+        self
+    }
+
+    /// Was the node created by this entry?
+    pub fn is_new(&self) -> bool {
+        self.inserted
+    }
+
+    /// The node's id
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The node's value
+    pub fn node(&self) -> &T {
+        &self.graph.nodes[self.id].node
+    }
+
+    /// The node's current score
+    pub fn score(&self) -> f64 {
+        self.graph.nodes[self.id].score
+    }
+
+    /// Overwrite the node's current score
+    pub fn set_score(&mut self, score: f64) {
+        self.graph.nodes[self.id].score = score;
+    }
+
+    /// Source ids of the node's incoming edges
+    pub fn incoming_ids(&self) -> &[usize] {
+        &self.graph.nodes[self.id].incoming_edges
+    }
+
+    /// Target ids of the node's outgoing edges
+    pub fn outgoing_ids(&self) -> &[usize] {
+        &self.graph.nodes[self.id].outgoing_edges
+    }
+
+    /// Number of incoming edges
+    pub fn in_degree(&self) -> usize {
+        self.incoming_ids().len()
+    }
+
+    /// Number of outgoing edges
+    pub fn out_degree(&self) -> usize {
+        self.outgoing_ids().len()
+    }
+}
+
+impl<T> Default for Pagerank<T>
+where
+    T: Eq + Hash + Clone 
+{
+    fn default() -> Self {
+        Self::new()
+    }    
+}
+
+impl<T> FromIterator<(T, T)> for Pagerank<T>
+where
+    T: Eq + Hash + Clone
+{
+    /// build a graph with one edge per (source, target) pair
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut pagerank = Pagerank::new();
+        for (source, target) in iter {
+            pagerank.add_edge(source, target);
+        }
+        pagerank
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    /// Yeah im aware i need more test coverage
+    use std::ops::Add;
+
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use crate::{
+        BowtieComponent, DanglingHandling, Direction, EdgeSource, EvictionPolicy, IterationMode, NodeRecord, Pagerank, ProgressReporter,
+        RunOutcome,
+    };
+
+    /// pagerank.nodes() copied out, so the graph can change in between
+    fn ranking<'a>(pagerank: &Pagerank<&'a str>) -> Vec<(&'a str, f64)> {
+        pagerank.nodes().iter().map(|(node, score)| (**node, *score)).collect()
+    }
+
+    #[test]
+    fn test_set_damping() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.set_damping_factor(22);
+
+        assert_eq!(0.22, pagerank.damping)
+    }
+
+    #[test]
+    fn test_damping_rescales_baseline() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        assert!(pagerank.set_damping(1.0).is_err());
+
+        pagerank.set_damping(0.5).unwrap();
+        pagerank.add_edge("ccc", "ddd");
+        assert_eq!(Some(0.5), pagerank.get_score("aaa"));
+        assert_eq!(pagerank.get_score("aaa"), pagerank.get_score("ddd"));
+
+        // computed scores are left alone until a reset
+        pagerank.calculate_step();
+        pagerank.set_damping_factor(80).unwrap();
+        assert_eq!(Some(0.75), pagerank.get_score("bbb"));
+        assert!((pagerank.get_score("aaa").unwrap() - 0.2).abs() < 1e-9);
+
+        pagerank.reset_scores();
+        assert!((pagerank.get_score("bbb").unwrap() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_damping_getters() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0.85, pagerank.damping());
+        assert_eq!(85, pagerank.damping_percent());
+
+        pagerank.set_damping_factor(57).unwrap();
+        assert_eq!(57, pagerank.damping_percent());
+    }
+
+    #[test]
+    fn test_three_nodes_are_created() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+        assert_eq!(3, pagerank.len())
+    }
+
+    #[test]
+    fn test_chained_edges() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "aaa")
+            .calculate();
+
+        assert_eq!(3, pagerank.len());
+        assert_eq!(3, pagerank.len_node());
+        assert_eq!(Some(true), pagerank.last_run_converged());
+        assert_eq!(pagerank.get_score("aaa"), pagerank.get_score("ccc"));
+    }
+
+    #[test]
+    fn test_density() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0f64, pagerank.density());
+
+        pagerank.add_edge("aaa", "aaa");
+        assert_eq!(0f64, pagerank.density());
+
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc");
+        assert_eq!(0.5, pagerank.density());
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa");
+
+        // same graph, different insertion order
+        let mut other = Pagerank::<&str>::new();
+        other.add_edge("ccc", "aaa").add_edge("bbb", "ccc").add_edge("aaa", "bbb");
+        assert!(pagerank.approx_eq(&other, 0.0));
+
+        other.pin_score("aaa", 0.16);
+        assert!(pagerank.approx_eq(&other, 0.02));
+        assert!(!pagerank.approx_eq(&other, 0.001));
+        assert!(!pagerank.approx_eq(&other, f64::NAN));
+
+        other.add_edge("aaa", "ccc");
+        assert!(!pagerank.approx_eq(&other, 0.02));
+    }
+
+    #[test]
+    fn test_add_edge_with_multiplicity() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge_with_multiplicity("aaa", "bbb", 3);
+        pagerank.add_edge_with_multiplicity("ccc", "ddd", 0);
+
+        let mut looped = Pagerank::<&str>::new();
+        looped.add_edge("aaa", "bbb").add_edge("aaa", "bbb").add_edge("aaa", "bbb");
+
+        assert_eq!(2, pagerank.len());
+        assert!(pagerank.approx_eq(&looped, 0.0));
+    }
+
+    #[test]
+    fn test_score_entropy() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0f64, pagerank.score_entropy());
+
+        // untouched scores are uniform
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "ddd");
+        assert!((pagerank.score_entropy() - 2f64).abs() < 1e-9);
+
+        pagerank.pin_score("aaa", 0.0);
+        pagerank.pin_score("bbb", 0.0);
+        pagerank.pin_score("ccc", 0.0);
+        assert_eq!(0f64, pagerank.score_entropy());
+    }
+
+    #[test]
+    fn test_simple_paths() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("aaa", "ccc")
+            .add_edge("ccc", "aaa")
+            .add_edge("ccc", "ddd");
+
+        assert_eq!(
+            vec![vec![&"aaa", &"bbb", &"ccc", &"ddd"], vec![&"aaa", &"ccc", &"ddd"]],
+            pagerank.simple_paths("aaa", "ddd", 3)
+        );
+        assert_eq!(vec![vec![&"aaa", &"ccc", &"ddd"]], pagerank.simple_paths("aaa", "ddd", 2));
+        assert_eq!(vec![vec![&"aaa"]], pagerank.simple_paths("aaa", "aaa", 2));
+        assert!(pagerank.simple_paths("ddd", "aaa", 5).is_empty());
+        assert!(pagerank.simple_paths("zzz", "aaa", 5).is_empty());
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let pagerank: Pagerank<&str> = vec![("aaa", "bbb"), ("bbb", "ccc"), ("zzz", "ccc")]
+            .into_iter()
+            .filter(|(source, _)| *source != "zzz")
+            .collect();
+
+        assert_eq!(3, pagerank.len());
+        assert_eq!(2, pagerank.len_node());
+        assert_eq!(Some(1), pagerank.get_incoming_edges("ccc"));
+    }
+
+    #[test]
+    fn test_degree_assortativity() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0f64, pagerank.degree_assortativity());
+
+        // a plain cycle has the same degrees everywhere
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+        assert_eq!(0f64, pagerank.degree_assortativity());
+
+        // the hub links into the popular node, the leaf into the quiet one
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("hub", "pop")
+            .add_edge("hub", "pop")
+            .add_edge("hub", "xxx")
+            .add_edge("leaf", "quiet");
+        assert!(pagerank.degree_assortativity() > 0f64);
+    }
+
+    #[test]
+    fn test_diff_and_apply_patch() {
+        let mut before = Pagerank::<&str>::new();
+        before.add_edge("aaa", "bbb").add_edge("aaa", "bbb").add_edge("bbb", "ccc");
+
+        let mut after = Pagerank::<&str>::new();
+        after.add_edge("aaa", "bbb").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+
+        let patch = before.diff(&after);
+        assert_eq!(vec![("aaa", "bbb"), ("bbb", "ccc")], patch.removed);
+        assert_eq!(vec![("ccc", "aaa"), ("ddd", "aaa")], patch.added);
+
+        before.apply_patch(&patch);
+        assert!(before.diff(&after).is_empty());
+        assert_eq!(3, before.len_node());
+        assert_eq!(Some(0), before.get_outgoing_edges("bbb"));
+    }
+
+    #[test]
+    fn test_estimate_diameter() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0, pagerank.estimate_diameter(3, 1));
+
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "ddd")
+            .add_edge("ddd", "aaa")
+            .add_edge("aaa", "ccc");
+
+        // sampling every node is exact
+        assert_eq!(3, pagerank.estimate_diameter(10, 1));
+        assert!(pagerank.estimate_diameter(1, 1) <= 3);
+        assert_eq!(pagerank.estimate_diameter(2, 7), pagerank.estimate_diameter(2, 7));
+    }
+
+    #[test]
+    fn test_eviction_policy() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert!(pagerank.set_eviction_policy(EvictionPolicy::LowestDegree, 1).is_err());
+        pagerank.set_eviction_policy(EvictionPolicy::LowestDegree, 3).unwrap();
+
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "bbb");
+        assert_eq!(3, pagerank.len());
+
+        // aaa has the fewest edges so it makes room for ddd
+        pagerank.add_edge("ddd", "bbb");
+        assert_eq!(3, pagerank.len());
+        assert_eq!(None, pagerank.get_score("aaa"));
+        assert_eq!(Some(2), pagerank.get_incoming_edges("bbb"));
+        assert_eq!(3, pagerank.len_node());
+
+        // edges between existing nodes never evict
+        pagerank.add_edge("ddd", "ccc");
+        assert_eq!(4, pagerank.len_node());
+
+        // a new self loop only needs one slot, ddd now goes
+        pagerank.add_edge("eee", "eee");
+        assert_eq!(3, pagerank.len());
+        assert_eq!(None, pagerank.get_score("ddd"));
+
+        // copies stay capped
+        let (mut copy, _) = pagerank.split_edges(0.0, 1);
+        copy.add_edge("fff", "ggg");
+        assert_eq!(3, copy.len());
+    }
+
+    #[test]
+    fn test_eviction_policy_node_creation() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.set_eviction_policy(EvictionPolicy::LowestDegree, 3).unwrap();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+
+        // nodes without edges get created under the cap too, the edgeless
+        // ones go first
+        pagerank.register_nodes(["ccc", "ddd"]);
+        assert_eq!(3, pagerank.len());
+        assert_eq!(None, pagerank.get_score("ccc"));
+        pagerank.pin_score("eee", 2.0);
+        pagerank.set_node_weight("fff", 2.0).unwrap();
+        pagerank.set_absorbing(vec!["ggg"]);
+        assert_eq!(3, pagerank.len());
+        assert_eq!(Some(1), pagerank.get_incoming_edges("aaa"));
+        assert!(pagerank.node_entry("hhh").is_new());
+        assert!(!pagerank.node_entry("hhh").is_new());
+        assert_eq!(3, pagerank.len());
+
+        // the union still gets a score for every node
+        let mut other = Pagerank::<&str>::new();
+        other.add_edge("iii", "jjj");
+        pagerank.calculate_over([&other], 1e-9);
+        assert_eq!(5, pagerank.len());
+    }
+
+    #[test]
+    fn test_score_gini() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(0f64, pagerank.score_gini());
+
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "ddd");
+        assert_eq!(0f64, pagerank.score_gini());
+
+        pagerank.pin_score("aaa", 0.0);
+        pagerank.pin_score("bbb", 0.0);
+        pagerank.pin_score("ccc", 0.0);
+        pagerank.pin_score("ddd", 1.0);
+        assert!((pagerank.score_gini() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sort_epsilon() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.register_nodes(vec!["aaa", "bbb", "ccc"]);
+        pagerank.pin_score("aaa", 1.0);
+        pagerank.pin_score("bbb", 1.0 + 1e-12);
+        pagerank.pin_score("ccc", 2.0);
+        fn order(pagerank: &Pagerank<&'static str>) -> Vec<&'static str> {
+            pagerank.nodes().iter().map(|(node, _)| **node).collect()
+        }
+
+        assert_eq!(vec!["ccc", "bbb", "aaa"], order(&pagerank));
+
+        pagerank.set_sort_epsilon(1e-9).unwrap();
+        assert_eq!(vec!["ccc", "aaa", "bbb"], order(&pagerank));
+        assert_eq!(vec!["ccc", "aaa", "bbb"], order(&pagerank.split_edges(0.0, 1).0));
+        assert!(pagerank.set_sort_epsilon(-1.0).is_err());
+        assert!(pagerank.set_sort_epsilon(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_nodes_sorted_by_value() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.register_nodes(vec!["ccc", "bbb", "aaa", "ddd"]);
+        pagerank.pin_score("ccc", 1.0);
+        pagerank.pin_score("bbb", 1.0);
+        pagerank.pin_score("aaa", 1.0 + 1e-12);
+        pagerank.pin_score("ddd", 2.0);
+        fn order(nodes: Vec<(&&'static str, f64)>) -> Vec<&'static str> {
+            nodes.iter().map(|(node, _)| **node).collect()
+        }
+
+        assert_eq!(vec!["ddd", "aaa", "ccc", "bbb"], order(pagerank.nodes()));
+        assert_eq!(vec!["ddd", "aaa", "bbb", "ccc"], order(pagerank.nodes_sorted_by_value()));
+
+        pagerank.set_sort_epsilon(1e-9).unwrap();
+        assert_eq!(vec!["ddd", "ccc", "bbb", "aaa"], order(pagerank.nodes()));
+        assert_eq!(vec!["ddd", "aaa", "bbb", "ccc"], order(pagerank.nodes_sorted_by_value()));
+    }
+
+    //Edge case
+    #[test]
+    fn test_only_one_node_created() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "aaa");
+        assert_eq!(1, pagerank.len())
+    }
+
+    #[test]
+    fn test_register_nodes() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.register_nodes(vec!["ccc", "bbb", "aaa"]);
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("ddd", "ccc");
+
+        assert_eq!(4, pagerank.len());
+        assert_eq!(2, pagerank.get_or_create_node("aaa"));
+        assert_eq!(0, pagerank.get_or_create_node("ccc"));
+        assert_eq!(3, pagerank.get_or_create_node("ddd"));
+    }
+
+    #[test]
+    fn test_incoming_edge_ids() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb").add_edge("aaa", "bbb");
+
+        let bbb = pagerank.try_node_id(&"bbb").unwrap();
+        assert_eq!(Some(&[0, 2, 0][..]), pagerank.incoming_edge_ids(bbb));
+        assert_eq!(Some(&[][..]), pagerank.incoming_edge_ids(0));
+        assert_eq!(None, pagerank.incoming_edge_ids(3));
+    }
+
+    #[test]
+    fn test_node_entry() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb");
+
+        let entry = pagerank.node_entry("bbb").or_insert_score(5.0);
+        assert!(!entry.is_new());
+        assert!((entry.score() - 0.15).abs() < 1e-9);
+        assert_eq!(&[0, 2][..], entry.incoming_ids());
+        assert_eq!(0, entry.out_degree());
+
+        let entry = pagerank.node_entry("ddd").or_insert_score(5.0);
+        assert!(entry.is_new());
+        assert_eq!(3, entry.id());
+        assert_eq!(&"ddd", entry.node());
+        assert_eq!(Some(5.0), pagerank.get_score("ddd"));
+    }
+
+    #[test]
+    fn test_try_node_id() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+
+        assert_eq!(Some(1), pagerank.try_node_id(&"bbb"));
+        assert_eq!(None, pagerank.try_node_id(&"ccc"));
+        assert_eq!(2, pagerank.len());
+    }
+
+    #[test]
+    fn test_edges(){
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        // gonna pack together multiple tests here
+
+        // test one edge
+        assert_eq!(Some(1), pagerank.get_incoming_edges("bbb"));
+        assert_eq!(Some(0), pagerank.get_incoming_edges("aaa")); // aaa has no incoming edges
+
+        // test two edges
+        pagerank.add_edge("ccc", "bbb");
+        assert_eq!(Some(2), pagerank.get_incoming_edges("bbb"));
+        assert_eq!(Some(0), pagerank.get_outgoing_edges("bbb"))
+    }
+
+    #[test]
+    fn test_incoming_neighbors() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("ccc", "bbb");
+
+        assert_eq!(Some(vec![&"aaa", &"ccc"]), pagerank.incoming_neighbors("bbb"));
+        assert_eq!(Some(vec![]), pagerank.incoming_neighbors("aaa"));
+        assert_eq!(None, pagerank.incoming_neighbors("zzz"));
+    }
+
+    #[test]
+    fn test_write_edge_list() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("ccc", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+
+        let mut out = Vec::new();
+        pagerank.write_edge_list(&mut out, '\t').unwrap();
+
+        assert_eq!("aaa\tbbb\nccc\tbbb\nbbb\tccc\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_deduplicate_by() {
+        let mut pagerank = Pagerank::<String>::new();
+        pagerank.add_edge("aaa".to_string(), "bbb".to_string());
+        pagerank.add_edge("AAA".to_string(), "bbb".to_string());
+        pagerank.add_edge("BBB".to_string(), "ccc".to_string());
+        pagerank.calculate();
+
+        pagerank.deduplicate_by(|node| node.to_lowercase());
+
+        assert_eq!(3, pagerank.len());
+        assert_eq!(3, pagerank.len_node());
+        assert_eq!(Some(2), pagerank.get_outgoing_edges("aaa".to_string()));
+        assert_eq!(Some(2), pagerank.get_incoming_edges("bbb".to_string()));
+        assert_eq!(Some(1), pagerank.get_outgoing_edges("bbb".to_string()));
+        assert_eq!(None, pagerank.get_score("AAA".to_string()));
+        assert_eq!(pagerank.get_score("aaa".to_string()), pagerank.get_score("ccc".to_string()));
+    }
+
+    #[test]
+    fn test_deduplicate_by_keeps_node_settings() {
+        let mut pagerank = Pagerank::<String>::new();
+        pagerank.add_edge("aaa".to_string(), "bbb".to_string());
+        pagerank.add_edge("AAA".to_string(), "bbb".to_string());
+        pagerank.pin_score("aaa".to_string(), 5.0);
+
+        pagerank.deduplicate_by(|node| node.to_lowercase());
+        assert_eq!(Some(5.0), pagerank.get_score("aaa".to_string()));
+        pagerank.calculate();
+        assert_eq!(Some(5.0), pagerank.get_score("aaa".to_string()));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn test_petgraph_round_trip() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("ccc", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+
+        let graph = pagerank.to_petgraph();
+        assert_eq!(3, graph.node_count());
+        assert_eq!(3, graph.edge_count());
+        assert!(graph.edge_weights().all(|w| *w == 1f64));
+
+        let back = Pagerank::from_petgraph(&graph.map(|_, n| *n, |_, _| ()));
+        assert_eq!(3, back.len());
+        assert_eq!(Some(2), back.get_incoming_edges("bbb"));
+        assert_eq!(Some(1), back.get_outgoing_edges("bbb"));
+    }
+
+    #[test]
+    fn test_to_vis_json() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_weighted_edge("a\"b", "c", 2.5);
+        pagerank.pin_score("c", 2.0);
+
+        assert_eq!(
+            r#"{"nodes":[{"id":"a\"b","score":0.15000000000000002},{"id":"c","score":2}],"edges":[{"source":"a\"b","target":"c","weight":2.5}]}"#,
+            pagerank.to_vis_json()
+        );
+    }
+
+    #[test]
+    fn test_write_scores_csv() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("a,b", "say \"hi\"");
+
+        let mut out = Vec::new();
+        pagerank.write_scores_csv(&mut out).unwrap();
+
+        let score = pagerank.get_score("a,b").unwrap();
+        assert_eq!(
+            format!("node,score\n\"a,b\",{score}\n\"say \"\"hi\"\"\",{score}\n"),
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_retain_above() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("ccc", "bbb")
+            .add_edge("bbb", "ddd")
+            .add_edge("ddd", "bbb");
+        pagerank.calculate();
+        let bbb = pagerank.get_score("bbb");
+
+        pagerank.retain_above(0.5);
+
+        assert_eq!(2, pagerank.len());
+        assert_eq!(2, pagerank.len_node());
+        assert_eq!(None, pagerank.get_score("aaa"));
+        assert_eq!(bbb, pagerank.get_score("bbb"));
+        assert_eq!(Some(1), pagerank.get_incoming_edges("bbb"));
+        assert_eq!(0, pagerank.get_or_create_node("bbb"));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("ccc", "aaa");
+
+        assert_eq!(Some(vec![&"bbb", &"ccc"]), pagerank.neighbors("aaa"));
+        assert_eq!(Some(vec![]), pagerank.neighbors("bbb"));
+        assert_eq!(None, pagerank.neighbors("zzz"));
+
+        // removals keep both directions in sync
+        pagerank.apply_patch(&pagerank.diff(&vec![("aaa", "ccc")].into_iter().collect()));
+        assert_eq!(Some(vec![&"ccc"]), pagerank.neighbors("aaa"));
+        assert_eq!(Some(vec![]), pagerank.neighbors("ccc"));
+    }
+
+    #[test]
+    fn test_score(){
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+        pagerank.add_edge("ddd", "aaa");
+        pagerank.add_edge("eee", "ddd");
+
+        // default score
+        assert_eq!(15_i64, (pagerank.get_score("aaa").expect("float") * 100_f64) as i64); 
+
+        // assert default score is shared across nodes
+        assert_eq!(pagerank.get_score("aaa"), pagerank.get_score("ddd")); 
+    }
+
+    #[test]
+    fn test_iteration() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+        pagerank.add_edge("ddd", "aaa");
+        pagerank.add_edge("eee", "ddd");
+
+        pagerank.calculate_step();
+        assert_eq!(
+            vec!["aaa", "bbb", "ccc", "ddd", "eee"],
+            pagerank.nodes()
+                .iter()
+                .map(|(node, _)| **node)
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_full_run() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ddd", "aaa");
+        pagerank.add_edge("eee", "ddd");
+
+        assert_eq!(16, pagerank.calculate());
+
+        assert_eq!(
+            vec!["aaa", "bbb", "ddd", "eee"],
+            pagerank.nodes()
+                .iter()
+                .map(|(node, _)| **node)
+                .collect::<Vec<&str>>()
+        );
+    }
+    #[test]
+    fn test_last_run_converged() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ddd", "aaa");
+        assert_eq!(None, pagerank.last_run_converged());
+
+        pagerank.calculate();
+        assert_eq!(Some(true), pagerank.last_run_converged());
+
+        // an impossible threshold always runs into the cap
+        pagerank.set_max_iterations(5).unwrap();
+        assert_eq!(5, pagerank.calculate_with_convergence(0.0));
+        assert_eq!(Some(false), pagerank.last_run_converged());
+        assert_eq!(Some(RunOutcome::Truncated), pagerank.last_run_outcome());
+    }
+
+    #[test]
+    fn test_stalled_residual() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+
+        // a metric stuck at a constant never reaches the threshold
+        assert_eq!(5, pagerank.calculate_with(0.1, |_, _| 0.5));
+        assert_eq!(Some(false), pagerank.last_run_converged());
+        assert_eq!(Some(RunOutcome::Stalled), pagerank.last_run_outcome());
+
+        // without detection it runs into the cap instead
+        assert!(pagerank.set_stall_detection(f64::NAN, 5).is_err());
+        assert!(pagerank.set_stall_detection(-1.0, 5).is_err());
+        pagerank.set_stall_detection(0.0, 0).unwrap();
+        pagerank.set_max_iterations(20).unwrap();
+        assert_eq!(20, pagerank.calculate_with(0.1, |_, _| 0.5));
+        assert_eq!(Some(RunOutcome::Truncated), pagerank.last_run_outcome());
+    }
+
+    #[test]
+    fn test_calculate_with_metric() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ddd", "aaa");
+
+        // largest absolute change of any single score
+        let iterations = pagerank.calculate_with(0.0001, |old, new| {
+            old.iter()
+                .zip(new.iter())
+                .map(|(a, b)| (a - b).abs())
+                .fold(0f64, f64::max)
+        });
+
+        assert!(iterations > 0);
+        assert_eq!(Some(true), pagerank.last_run_converged());
+        assert_eq!(
+            vec!["aaa", "bbb", "ddd"],
+            pagerank.nodes()
+                .iter()
+                .map(|(node, _)| **node)
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_gauss_seidel() {
+        let edges = vec![("aaa", "bbb"), ("bbb", "aaa"), ("ddd", "aaa"), ("eee", "ddd")];
+
+        let mut jacobi = Pagerank::<&str>::new();
+        let mut gauss = Pagerank::<&str>::new();
+        gauss.set_iteration_mode(IterationMode::GaussSeidel);
+        for (source, target) in edges {
+            jacobi.add_edge(source, target);
+            gauss.add_edge(source, target);
+        }
+
+        assert!(gauss.calculate_with_convergence(0.0001) <= jacobi.calculate_with_convergence(0.0001));
+
+        // both land on the same fixed point
+        for node in ["aaa", "bbb", "ddd", "eee"] {
+            let diff = gauss.get_score(node).unwrap() - jacobi.get_score(node).unwrap();
+            assert!(diff.abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_personalization_fn() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ccc", "ddd");
+        pagerank.add_edge("ddd", "ccc");
+
+        assert!(pagerank.set_personalization_fn(|_| -1f64).is_err());
+        assert!(pagerank.set_personalization_fn(|_| 0f64).is_err());
+
+        // only the c/d cycle gets teleported to
+        pagerank
+            .set_personalization_fn(|node| if node.starts_with('c') { 1f64 } else { 0f64 })
+            .unwrap();
+        pagerank.calculate_with_convergence(0.0001);
+
+        assert!(pagerank.get_score("aaa").unwrap() < 0.01);
+        assert!(pagerank.get_score("ccc").unwrap() > pagerank.get_score("ddd").unwrap());
+        let total = pagerank.nodes().iter().map(|(_, score)| score).sum::<f64>();
+        assert!((total - 4f64).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_personalized_dangling_teleport() {
+        // ccc and ddd are sinks, eee has no personalization and no links in
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "aaa")
+            .add_edge("bbb", "ccc")
+            .add_edge("aaa", "ddd")
+            .add_edge("eee", "aaa");
+        pagerank
+            .set_personalization_fn(|node| match *node {
+                "aaa" => 3f64,
+                "ccc" => 1f64,
+                _ => 0f64,
+            })
+            .unwrap();
+        pagerank.set_dangling_handling(DanglingHandling::Teleport);
+        pagerank.calculate_with_convergence(1e-12);
+
+        let total = pagerank.nodes().iter().map(|(_, score)| score).sum::<f64>();
+        let p = |node: &str| pagerank.get_score(node).unwrap() / total;
+        assert!((["aaa", "bbb", "ccc", "ddd", "eee"].iter().map(|node| p(node)).sum::<f64>() - 1f64).abs() < 1e-12);
+        assert!((total - 5f64).abs() < 1e-6);
+
+        // fixed point of the canonical formula with p = (0.75, 0, 0.25, 0, 0)
+        let d = 0.85;
+        let personal = HashMap::from([("aaa", 0.75), ("ccc", 0.25)]);
+        let dangling = p("ccc") + p("ddd");
+        let links = HashMap::from([
+            ("aaa", p("bbb") / 2f64 + p("eee")),
+            ("bbb", p("aaa") / 2f64),
+            ("ccc", p("bbb") / 2f64),
+            ("ddd", p("aaa") / 2f64),
+            ("eee", 0f64),
+        ]);
+        for (node, link) in links {
+            let t = personal.get(node).copied().unwrap_or(0f64);
+            assert!((p(node) - ((1f64 - d) * t + d * (link + dangling * t))).abs() < 1e-9);
+        }
+        assert_eq!(0f64, p("eee"));
+    }
+
+    #[test]
+    fn test_calculate_with_deadline() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ddd", "aaa");
+        assert_eq!(None, pagerank.last_residual());
+
+        // no time at all means no work
+        assert_eq!(0, pagerank.calculate_with_deadline(Duration::ZERO));
+        assert_eq!(Some(RunOutcome::Truncated), pagerank.last_run_outcome());
+        assert_eq!(None, pagerank.last_residual());
+
+        // plenty of time converges like calculate does
+        pagerank.calculate_with_deadline(Duration::from_secs(60));
+        assert_eq!(Some(true), pagerank.last_run_converged());
+        assert!(pagerank.last_residual().unwrap() < 0.01);
+    }
+
+    #[test]
+    fn test_pin_score() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "ccc");
+        pagerank.pin_score("aaa", 2.0);
+
+        pagerank.calculate_with_convergence(0.0001);
+        assert_eq!(Some(2.0), pagerank.get_score("aaa"));
+        // bbb = 0.15 + 0.85 * 2.0
+        assert!((pagerank.get_score("bbb").unwrap() - 1.85).abs() < 0.001);
+
+        pagerank.unpin_score("aaa");
+        pagerank.calculate_with_convergence(0.0001);
+        assert!((pagerank.get_score("aaa").unwrap() - 0.15).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_local_rank() {
+        let mut pr = Pagerank::<&str>::new();
+        pr.add_edge("D", "A")
+            .add_edge("D", "B")
+            .add_edge("B", "C")
+            .add_edge("C", "B")
+            .add_edge("E", "B")
+            .add_edge("E", "F")
+            .add_edge("F", "B")
+            .add_edge("F", "E")
+            .add_edge("G", "B")
+            .add_edge("G", "E");
+
+        let local = pr
+            .local_rank(&["B", "E", "nope"], 1e-9)
+            .into_iter()
+            .map(|(node, score)| (*node, score))
+            .collect::<HashMap<&str, f64>>();
+        assert_eq!(2, local.len());
+
+        pr.calculate_with_convergence(1e-12);
+        for node in ["B", "E"] {
+            assert!((local[&node] - pr.get_score(node).unwrap()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_restart_every() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa");
+        pagerank.add_edge("ddd", "aaa");
+        assert!(pagerank.set_restart_every(Some(0)).is_err());
+
+        pagerank.set_restart_every(Some(3)).unwrap();
+        pagerank.calculate_step();
+        pagerank.calculate_step();
+        assert_ne!(Some(1.0), pagerank.get_score("aaa"));
+
+        // third step teleports everyone back to the uniform share
+        pagerank.calculate_step();
+        for node in ["aaa", "bbb", "ccc", "ddd"] {
+            assert_eq!(Some(1.0), pagerank.get_score(node));
+        }
+    }
+
+    struct Cycle(usize);
+
+    impl EdgeSource<usize> for Cycle {
+        fn edges(&self) -> impl Iterator<Item = (usize, usize)> {
+            let n = self.0;
+            (0..n).map(move |i| (i, (i + 1) % n)).chain([(n, 0)])
+        }
+    }
+
+    #[test]
+    fn test_calculate_streaming() {
+        let source = Cycle(4);
+
+        let mut streamed = Pagerank::<usize>::new();
+        streamed.calculate_streaming(&source, 0.0001);
+        assert_eq!(Some(true), streamed.last_run_converged());
+        assert_eq!(0, streamed.len_node()); // nothing was stored
+
+        let mut stored = Pagerank::<usize>::new();
+        for (source, target) in source.edges() {
+            stored.add_edge(source, target);
+        }
+        stored.calculate_with_convergence(0.0001);
+
+        assert_eq!(stored.len(), streamed.len());
+        for node in 0..=4 {
+            let diff = stored.get_score(node).unwrap() - streamed.get_score(node).unwrap();
+            assert!(diff.abs() < 1e-9);
+        }
+
+        // per node settings and laziness go through the same update
+        for pagerank in [&mut streamed, &mut stored] {
+            pagerank.set_laziness(0.7).unwrap();
+            pagerank.set_absorbing(vec![1]);
+            pagerank.set_node_damping(2, 0.5).unwrap();
+            pagerank.reset_scores();
+        }
+        streamed.calculate_streaming(&source, 1e-12);
+        stored.calculate_with_convergence(1e-12);
+        for node in 0..=4 {
+            assert!((stored.get_score(node).unwrap() - streamed.get_score(node).unwrap()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_calculate_with_timing() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb");
+        pagerank.add_edge("bbb", "aaa");
+        pagerank.add_edge("ddd", "aaa");
         pagerank.add_edge("eee", "ddd");
 
+        let timings = pagerank.calculate_with_timing(0.01);
+
+        // the converging step gets recorded too
+        assert_eq!(17, timings.len());
+        assert!(timings.last().unwrap().0 < 0.01);
+        assert!(timings[..16].iter().all(|(residual, _)| *residual >= 0.01));
+    }
+
+    #[test]
+    fn test_weighted_undirected_edge() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_weighted_undirected_edge("aaa", "bbb", 3.0)
+            .add_weighted_undirected_edge("aaa", "ccc", 1.0)
+            .add_weighted_undirected_edge("ccc", "ccc", 2.0);
+
+        assert_eq!(5, pagerank.len_node());
+        assert_eq!(
+            vec![(&"bbb", &"aaa", 3.0), (&"aaa", &"bbb", 3.0), (&"ccc", &"ccc", 2.0)],
+            pagerank.edges_by_weight()[..3]
+        );
+        pagerank.calculate();
+        assert!(pagerank.get_score("aaa").unwrap() > pagerank.get_score("bbb").unwrap());
+    }
+
+    #[test]
+    fn test_laziness() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+        pagerank.pin_score("aaa", 2.0);
+        pagerank.unpin_score("aaa");
+        assert!(pagerank.set_laziness(0.0).is_err());
+        assert!(pagerank.set_laziness(1.5).is_err());
+        pagerank.set_laziness(0.9).unwrap();
+
+        pagerank.calculate_step();
+        // bbb moves 90% of the way from 0.15 towards 0.15 + 0.85 * 2
+        let expected = 0.1 * 0.15 + 0.9 * (0.15 + 0.85 * 2.0);
+        assert!((pagerank.get_score("bbb").unwrap() - expected).abs() < 1e-9);
+
+        pagerank.calculate_with_convergence(1e-9);
+        assert!((pagerank.get_score("aaa").unwrap() - 1f64).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_effective_out_degree() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("bbb", "ccc");
+        assert_eq!(2f64, pagerank.effective_out_degree(&"aaa"));
+        assert_eq!(0f64, pagerank.effective_out_degree(&"ccc"));
+        assert_eq!(0f64, pagerank.effective_out_degree(&"zzz"));
+
+        pagerank.set_dangling_handling(DanglingHandling::Teleport);
+        assert_eq!(3f64, pagerank.effective_out_degree(&"ccc"));
+        pagerank.set_teleport_excluded(vec!["bbb"]);
+        assert_eq!(2f64, pagerank.effective_out_degree(&"ccc"));
+        pagerank.set_dangling_sink(vec!["aaa"]);
+        assert_eq!(1f64, pagerank.effective_out_degree(&"ccc"));
+        pagerank.set_absorbing(vec!["aaa"]);
+        assert_eq!(1f64, pagerank.effective_out_degree(&"aaa"));
+    }
+
+    #[test]
+    fn test_set_teleport_excluded() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("ccc", "aaa").add_edge("aaa", "ccc");
+        pagerank.set_teleport_excluded(vec!["ccc", "zzz"]);
+        pagerank.calculate_with_convergence(1e-12);
+
+        // ccc only gets what aaa links over, zzz gets nothing at all
+        let aaa = pagerank.get_score("aaa").unwrap();
+        assert!((pagerank.get_score("ccc").unwrap() - pagerank.damping() * aaa / 2f64).abs() < 1e-9);
+        assert_eq!(Some(0f64), pagerank.get_score("zzz"));
+        // the teleport mass all went to aaa and bbb instead
+        assert!((pagerank.nodes().iter().map(|(_, score)| score).sum::<f64>() - 4f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_robustness() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "aaa")
+            .add_edge("ddd", "aaa")
+            .add_edge("aaa", "ddd");
+        pagerank.calculate();
+        let before = ranking(&pagerank);
+
+        let spread = pagerank.robustness(0.4, 5, 7, 1e-9);
+        assert_eq!(5, pagerank.len_node());
+        assert_eq!(before, ranking(&pagerank));
+        assert_eq!(4, spread.len());
+        assert!(spread.values().any(|sd| *sd > 1e-3));
+        assert_eq!(spread, pagerank.robustness(0.4, 5, 7, 1e-9));
+        assert!(pagerank.robustness(0.0, 3, 7, 1e-9).values().all(|sd| *sd < 1e-9));
+    }
+
+    #[test]
+    fn test_warm_start_from() {
+        let edges = [("aaa", "bbb"), ("bbb", "ccc"), ("ccc", "aaa"), ("ccc", "bbb")];
+        let mut previous = edges.iter().copied().collect::<Pagerank<&str>>();
+        previous.add_edge("zzz", "aaa");
+        previous.calculate_with_convergence(1e-9);
+
+        let mut cold = edges.iter().copied().collect::<Pagerank<&str>>();
+        let mut warm = edges.iter().copied().collect::<Pagerank<&str>>();
+        cold.add_edge("ddd", "aaa");
+        warm.add_edge("ddd", "aaa");
+        warm.warm_start_from(&previous);
+        assert_eq!(previous.get_score("bbb"), warm.get_score("bbb"));
+        assert_eq!(Some(1f64 - warm.damping()), warm.get_score("ddd"));
+
+        assert!(warm.calculate_with_convergence(1e-9) < cold.calculate_with_convergence(1e-9));
+    }
+
+    #[test]
+    fn test_group_flow() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("bbb", "aaa").add_edge("ccc", "aaa");
+        pagerank.calculate();
+
+        let score = |pagerank: &Pagerank<&str>, node| pagerank.get_score(node).unwrap();
+        let flow = pagerank.group_flow(|node| *node == "ccc");
+        let d = pagerank.damping();
+        assert_eq!(3, flow.len());
+        assert!((flow[&(false, true)] - d * score(&pagerank, "aaa") / 2f64).abs() < 1e-9);
+        assert!((flow[&(true, false)] - d * score(&pagerank, "ccc")).abs() < 1e-9);
+        assert!((flow[&(false, false)] - d * (score(&pagerank, "aaa") / 2f64 + score(&pagerank, "bbb"))).abs() < 1e-9);
+
+        // an absorbing bbb holds on to its score, ccc only passes on half
+        pagerank.set_absorbing(vec!["bbb"]);
+        pagerank.set_node_damping("ccc", 0.5).unwrap();
+        pagerank.calculate();
+        let flow = pagerank.group_flow(|node| *node);
+        assert_eq!(None, flow.get(&("bbb", "aaa")));
+        assert!((flow[&("bbb", "bbb")] - d * score(&pagerank, "bbb")).abs() < 1e-9);
+        assert!((flow[&("aaa", "ccc")] - 0.5 * score(&pagerank, "aaa") / 2f64).abs() < 1e-9);
+        assert!((flow[&("ccc", "aaa")] - d * score(&pagerank, "ccc")).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_absorbing() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa");
+        pagerank.set_absorbing(vec!["bbb"]);
+        pagerank.calculate_with_convergence(1e-12);
+
+        let baseline = 1f64 - pagerank.damping();
+        assert!((pagerank.get_score("ccc").unwrap() - baseline).abs() < 1e-9);
+        assert!((pagerank.get_score("aaa").unwrap() - baseline * (1f64 + pagerank.damping())).abs() < 1e-9);
+        assert!((pagerank.nodes().iter().map(|(_, score)| score).sum::<f64>() - 3f64).abs() < 1e-9);
+
+        // the breakdowns agree with what the update actually does
+        assert_eq!(Some(vec![]), pagerank.score_breakdown("ccc"));
+        assert_eq!(Some(0f64), pagerank.outgoing_influence("bbb"));
+        let held = pagerank.score_breakdown("bbb").unwrap();
+        assert_eq!(vec![&"aaa", &"bbb"], held.iter().map(|(node, _)| *node).collect::<Vec<&&str>>());
+        let total = baseline + held.iter().map(|(_, share)| share).sum::<f64>();
+        assert!((total - pagerank.get_score("bbb").unwrap()).abs() < 1e-9);
+
+        pagerank.set_absorbing(vec![]);
+        pagerank.calculate_with_convergence(1e-12);
+        assert!((pagerank.get_score("ccc").unwrap() - 1f64).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_bidirectional() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("bbb", "ccc");
+        let mut forward = Pagerank::<&str>::new();
+        forward.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("bbb", "ccc");
+        let mut reverse = Pagerank::<&str>::new();
+        reverse.add_edge("bbb", "aaa").add_edge("ccc", "aaa").add_edge("ccc", "bbb");
+        forward.calculate_with_convergence(1e-9);
+        reverse.calculate_with_convergence(1e-9);
+
+        assert!(pagerank.calculate_bidirectional(0.25, 1e-9) > 0);
+        for node in ["aaa", "bbb", "ccc"] {
+            let expected = 0.25 * forward.get_score(node).unwrap() + 0.75 * reverse.get_score(node).unwrap();
+            assert!((pagerank.get_score(node).unwrap() - expected).abs() < 1e-6);
+        }
+        assert_eq!(Some(RunOutcome::Converged), pagerank.last_run_outcome());
+
+        // the forward run converges in time but the reverse one doesn't
+        let (mut pagerank, mut forward, mut reverse) = (Pagerank::<&str>::new(), Pagerank::new(), Pagerank::new());
+        for graph in [&mut pagerank, &mut forward] {
+            graph.add_edge("aaa", "bbb").add_edge("aaa", "ccc").add_edge("bbb", "aaa");
+        }
+        reverse.add_edge("bbb", "aaa").add_edge("ccc", "aaa").add_edge("aaa", "bbb");
+        for graph in [&mut pagerank, &mut forward, &mut reverse] {
+            graph.set_max_iterations(10).unwrap();
+        }
+        let forward = forward.calculate_with_convergence(1e-3);
+        assert_eq!(10, reverse.calculate_with_convergence(1e-3));
+
+        assert_eq!(forward + 10, pagerank.calculate_bidirectional(0.5, 1e-3));
+        assert_eq!(Some(RunOutcome::Truncated), pagerank.last_run_outcome());
+        assert_eq!(reverse.last_residual(), pagerank.last_residual());
+    }
+
+    #[test]
+    fn test_new_deterministic() {
+        let mut pagerank = Pagerank::<&str>::new_deterministic();
+        let mut random = Pagerank::<&str>::new();
+        for graph in [&mut pagerank, &mut random] {
+            graph.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+            graph.calculate();
+        }
+        assert_eq!(ranking(&random), ranking(&pagerank));
+
+        // lookups keep working after ids get repacked and in copies
+        pagerank.retain_above(0.2);
+        assert_eq!(None, pagerank.get_score("ddd"));
+        assert_eq!(Some(1), pagerank.get_incoming_edges("aaa"));
+        assert_eq!(Some(1), pagerank.split_edges(0.0, 1).0.get_incoming_edges("ccc"));
+
+        // nothing public walks the lookup table, so check its order directly.
+        // two RandomState tables would put 100 keys in different orders
+        let (mut first, mut second) = (Pagerank::<usize>::new_deterministic(), Pagerank::<usize>::new_deterministic());
+        first.register_nodes(0..100);
+        second.register_nodes(0..100);
+        assert!(first.node_positions.keys().eq(second.node_positions.keys()));
+        assert!(first.split_edges(0.0, 1).0.node_positions.keys().eq(second.node_positions.keys()));
+    }
+
+    #[test]
+    fn test_predict_rank() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa");
+        pagerank.calculate();
+        let before = ranking(&pagerank);
+
+        let predicted = pagerank.predict_rank(&["aaa", "bbb", "zzz"], 1e-9);
+        assert_eq!(before, ranking(&pagerank));
+        assert_eq!(3, pagerank.len());
+
+        pagerank.add_edge("aaa", "ddd").add_edge("bbb", "ddd");
+        pagerank.calculate_with_convergence(1e-9);
+        assert!((pagerank.get_score("ddd").unwrap() - predicted).abs() < 1e-6);
+        assert_eq!(1f64 - pagerank.damping(), Pagerank::<&str>::new().predict_rank(&[], 1e-9));
+    }
+
+    #[test]
+    fn test_node_records() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb").add_edge("bbb", "aaa");
+        pagerank.calculate();
+
+        let records = pagerank.node_records();
+        assert_eq!(vec![&"aaa", &"bbb", &"ccc"], records.iter().map(|r| r.node).collect::<Vec<_>>());
+        assert_eq!(NodeRecord { node: &"bbb", score: pagerank.get_score("bbb").unwrap(), in_degree: 2, out_degree: 1 }, records[1]);
+        assert_eq!((0, 1), (records[2].in_degree, records[2].out_degree));
+    }
+
+    #[test]
+    fn test_auto_stabilize() {
+        // starting from all ones the scores on this star swing back and
+        // forth every step
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "ccc").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ccc", "bbb");
+        pagerank.map_scores(|_| 1f64).unwrap();
+        pagerank.calculate_with_convergence(1e-9);
+        assert_eq!(1f64, pagerank.laziness());
+
+        pagerank.set_auto_stabilize(true);
+        pagerank.map_scores(|_| 1f64).unwrap();
+        pagerank.calculate_with_convergence(1e-9);
+        assert_eq!(0.9, pagerank.laziness());
+        assert_eq!(Some(RunOutcome::Converged), pagerank.last_run_outcome());
+    }
+
+    #[test]
+    fn test_top_k_by_category() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("a1", "a2")
+            .add_edge("b1", "a2")
+            .add_edge("a3", "a1")
+            .add_edge("b2", "b3");
+        pagerank.calculate();
+
+        let top = pagerank.top_k_by_category(2, |node| node.chars().next().unwrap());
+        let names = |c| top[&c].iter().map(|(node, _)| **node).collect::<Vec<&str>>();
+        assert_eq!(vec!["a2", "a1"], names('a'));
+        assert_eq!(vec!["b3", "b1"], names('b'));
+        assert_eq!(2, top.len());
+    }
+
+    #[test]
+    fn test_dynamic_teleport() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("ccc", "ddd").add_edge("ddd", "ccc");
+
+        // a fixed map behaves like the same static personalization
+        let mut fixed = Pagerank::<&str>::new();
+        fixed.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("ccc", "ddd").add_edge("ddd", "ccc");
+        fixed.set_personalization_fn(|node| if *node == "ccc" { 1f64 } else { 0f64 }).unwrap();
+        fixed.calculate_with_convergence(1e-9);
+        let mut seen = Vec::new();
+        pagerank.calculate_with_dynamic_teleport(1e-9, |iteration| {
+            seen.push(iteration);
+            HashMap::from([("ccc", 1f64), ("zzz", 5f64)])
+        });
+        assert_eq!(fixed.nodes(), pagerank.nodes());
+        assert_eq!((0..seen.len() as i32).collect::<Vec<i32>>(), seen);
+
+        // interest moving over to aaa drags its cycle up
+        pagerank.reset_scores();
+        pagerank.calculate_with_dynamic_teleport(1e-9, |iteration| match iteration < 5 {
+            true => HashMap::from([("ccc", 1f64)]),
+            false => HashMap::from([("aaa", 1f64)]),
+        });
+        assert!(pagerank.get_score("aaa").unwrap() > pagerank.get_score("ccc").unwrap());
+    }
+
+    #[test]
+    fn test_edge_multiplicity() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge_with_multiplicity("aaa", "bbb", 3).add_edge("bbb", "aaa");
+
+        assert_eq!(3, pagerank.edge_multiplicity(&"aaa", &"bbb"));
+        assert_eq!(1, pagerank.edge_multiplicity(&"bbb", &"aaa"));
+        assert_eq!(0, pagerank.edge_multiplicity(&"aaa", &"aaa"));
+        assert_eq!(0, pagerank.edge_multiplicity(&"aaa", &"zzz"));
+    }
+
+    #[test]
+    fn test_calculate_eigenvector() {
+        // a star, the hub's share is 1/sqrt(2) of the unit vector
+        let mut pagerank = Pagerank::<&str>::new();
+        for leaf in ["aaa", "bbb", "ccc", "ddd"] {
+            pagerank.add_weighted_undirected_edge("hub", leaf, 1.0);
+        }
+        pagerank.calculate_eigenvector(1e-12);
+
+        assert_eq!(Some(RunOutcome::Converged), pagerank.last_run_outcome());
+        assert!((pagerank.get_score("hub").unwrap() - 0.5f64.sqrt()).abs() < 1e-6);
+        assert!((pagerank.get_score("aaa").unwrap() - 0.125f64.sqrt()).abs() < 1e-6);
+        let length = pagerank.nodes().iter().map(|(_, score)| score * score).sum::<f64>();
+        assert!((length - 1f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jaccard_similarity() {
+        let mut before = Pagerank::<&str>::new();
+        before.add_edge("aaa", "bbb").add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa");
+        let mut after = Pagerank::<&str>::new();
+        after.add_edge("ccc", "aaa").add_edge("aaa", "bbb").add_edge("bbb", "ddd");
+
+        // 2 shared out of aaa-bbb, bbb-ccc, ccc-aaa, bbb-ddd
+        assert_eq!(0.5, before.jaccard_similarity(&after));
+        assert_eq!(1f64, before.jaccard_similarity(&before));
+        assert_eq!(1f64, Pagerank::<&str>::new().jaccard_similarity(&Pagerank::new()));
+        assert_eq!(0f64, before.jaccard_similarity(&Pagerank::new()));
+    }
+
+    #[test]
+    fn test_damping_for_iterations() {
+        let pagerank = Pagerank::<&str>::new();
+        let damping = pagerank.damping_for_iterations(50, 1e-4).unwrap();
+        assert!((damping.ln() * 50f64 - 1e-4f64.ln()).abs() < 1e-9);
+        assert!(pagerank.damping_for_iterations(100, 1e-4).unwrap() > damping);
+        assert!(pagerank.damping_for_iterations(10, 2.0).unwrap() < 1f64);
+        assert!(pagerank.damping_for_iterations(0, 1e-4).is_err());
+        assert!(pagerank.damping_for_iterations(10, 0.0).is_err());
+        assert!(pagerank.damping_for_iterations(10, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_harmonic_centrality() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ddd", "ccc");
+
+        let exact = pagerank.calculate_harmonic_centrality(None, 1);
+        assert_eq!(0f64, exact[&"aaa"]);
+        assert_eq!(1f64, exact[&"bbb"]);
+        assert_eq!(1f64 + 1f64 + 0.5, exact[&"ccc"]);
+
+        let sampled = pagerank.calculate_harmonic_centrality(Some(2), 7);
+        assert_eq!(4, sampled.len());
+        assert_eq!(sampled, pagerank.calculate_harmonic_centrality(Some(2), 7));
+        assert_eq!(exact, pagerank.calculate_harmonic_centrality(Some(10), 1));
+    }
+
+    #[test]
+    fn test_observe_edge() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb");
+        pagerank.calculate();
+
+        pagerank.set_snapshot_iterations(vec![2, 3]);
+        let ranking = pagerank.observe_edge("bbb", "ddd", 2);
+        assert_eq!(4, ranking.len());
+        assert_eq!(&"ddd", ranking[0].0);
+        assert!(pagerank.snapshot(2).is_some());
+        assert_eq!(None, pagerank.snapshot(3));
+
+        pagerank.observe_edge("ddd", "eee", 0);
+        assert_eq!(Some(1f64 - pagerank.damping()), pagerank.get_score("eee"));
+    }
+
+    #[test]
+    fn test_bowtie_classification() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("core1", "core2")
+            .add_edge("core2", "core3")
+            .add_edge("core3", "core1")
+            .add_edge("in", "core1")
+            .add_edge("core2", "out")
+            .add_edge("in", "tendril")
+            .add_edge("in", "tube")
+            .add_edge("tube", "out")
+            .add_edge("lone1", "lone2");
+
+        let classes = pagerank.bowtie_classification();
+        assert_eq!(BowtieComponent::Core, classes[&"core1"]);
+        assert_eq!(BowtieComponent::Core, classes[&"core3"]);
+        assert_eq!(BowtieComponent::In, classes[&"in"]);
+        assert_eq!(BowtieComponent::Out, classes[&"out"]);
+        assert_eq!(BowtieComponent::Tendril, classes[&"tendril"]);
+        assert_eq!(BowtieComponent::Tube, classes[&"tube"]);
+        assert_eq!(BowtieComponent::Disconnected, classes[&"lone2"]);
+        assert!(Pagerank::<&str>::new().bowtie_classification().is_empty());
+    }
+
+    #[test]
+    fn test_outgoing_influence() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "ccc")
+            .add_edge("aaa", "bbb")
+            .add_edge("ccc", "aaa");
+        pagerank.calculate();
+        let score = pagerank.get_score("aaa").unwrap();
+
+        let breakdown = pagerank.influence_breakdown("aaa").unwrap();
+        assert_eq!(vec![&"bbb", &"bbb", &"ccc"], breakdown.iter().map(|(node, _)| *node).collect::<Vec<&&str>>());
+        assert!((pagerank.outgoing_influence("aaa").unwrap() - 0.85 * score).abs() < 1e-9);
+        assert_eq!(Some(0f64), pagerank.outgoing_influence("bbb"));
+        assert_eq!(None, pagerank.outgoing_influence("zzz"));
+    }
+
+    #[test]
+    fn test_calculate_filtered() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "xxx")
+            .add_edge("xxx", "bbb")
+            .add_edge("bbb", "aaa");
+        pagerank.calculate_filtered(|source, target| *source != "xxx" && *target != "xxx", 1e-9);
+
+        let mut filtered = Pagerank::<&str>::new();
+        filtered.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+        filtered.register_nodes(["xxx"]);
+        filtered.calculate_with_convergence(1e-9);
+        for node in ["aaa", "bbb", "xxx"] {
+            assert!((pagerank.get_score(node).unwrap() - filtered.get_score(node).unwrap()).abs() < 1e-6);
+        }
+        assert_eq!(4, pagerank.len_node());
+    }
+
+    #[test]
+    fn test_calculate_with_reporter() {
+        struct Log(Vec<(i32, f64)>);
+        impl ProgressReporter for Log {
+            fn on_iteration(&mut self, iter: i32, residual: f64) {
+                self.0.push((iter, residual));
+            }
+        }
+
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+        let mut log = Log(Vec::new());
+        let iterations = pagerank.calculate_with_reporter(0.0001, &mut log);
+
+        assert_eq!(iterations as usize + 1, log.0.len());
+        assert_eq!((1..=log.0.len() as i32).collect::<Vec<i32>>(), log.0.iter().map(|(i, _)| *i).collect::<Vec<i32>>());
+        assert_eq!(pagerank.last_residual(), log.0.last().map(|(_, residual)| *residual));
+    }
+
+    #[test]
+    fn test_split_edges() {
+        let pagerank = (0..20usize).map(|i| (i, (i * 7 + 1) % 20)).collect::<Pagerank<usize>>();
+
+        let (train, held_out) = pagerank.split_edges(0.25, 42);
+        assert_eq!(5, held_out.len());
+        assert_eq!(15, train.len_node());
+        assert_eq!(20, train.len());
+        for (source, target) in held_out.iter() {
+            assert_eq!(Some(0), train.get_outgoing_edges(*source));
+            assert_eq!(Some(vec![target]), pagerank.neighbors(*source));
+        }
+
+        assert_eq!(held_out, pagerank.split_edges(0.25, 42).1);
+        assert_ne!(held_out, pagerank.split_edges(0.25, 7).1);
+        assert_eq!(20, pagerank.split_edges(2.0, 1).1.len());
+
+        // parallel copies with different weights, whichever copy got
+        // sampled is the one that goes
+        let mut parallel = Pagerank::<&str>::new();
+        parallel
+            .add_weighted_edge("aaa", "bbb", 1.0)
+            .add_weighted_edge("aaa", "bbb", 2.0)
+            .add_weighted_edge("aaa", "bbb", 4.0);
+        let mut left = (0..30u64)
+            .map(|seed| {
+                let (train, _) = parallel.split_edges(1.0 / 3.0, seed);
+                train.edges_by_weight().iter().map(|(_, _, weight)| weight).sum::<f64>()
+            })
+            .collect::<Vec<f64>>();
+        left.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        left.dedup();
+        assert_eq!(vec![3.0, 5.0, 6.0], left);
+    }
+
+    #[test]
+    fn test_damping_stability() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("ccc", "bbb")
+            .add_edge("bbb", "ddd")
+            .add_edge("ddd", "eee")
+            .add_edge("fff", "eee");
+        pagerank.calculate();
+        let before = ranking(&pagerank);
+
+        pagerank.set_snapshot_iterations(vec![1]);
+        let stability = pagerank.damping_stability(&[0.85, 0.5, 1.5], 1e-9);
+        assert_eq!(None, pagerank.snapshot(1));
+        assert_eq!((0.85, 1f64), stability[0]);
+        assert!(stability[1].1 < 1f64);
+        assert!(stability[2].1.is_nan());
+
+        assert_eq!(0.85, pagerank.damping());
+        assert_eq!(before, ranking(&pagerank));
+    }
+
+    #[test]
+    fn test_weighted_in_degree() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("aaa", "bbb").add_edge("ccc", "bbb").add_edge("ddd", "ccc");
+        pagerank.calculate();
+        let score = |node| pagerank.get_score(node).unwrap();
+
+        assert_eq!(Some(2f64 * score("aaa") + score("ccc")), pagerank.weighted_in_degree("bbb"));
+        assert_eq!(Some(0f64), pagerank.weighted_in_degree("ddd"));
+        assert_eq!(None, pagerank.weighted_in_degree("zzz"));
+    }
+
+    #[test]
+    fn test_calculate_bounded() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+
+        // 4 nodes, so 10 updates buys 2 full steps
+        assert_eq!(2, pagerank.calculate_bounded(1e-12, 10));
+        assert_eq!(Some(RunOutcome::Truncated), pagerank.last_run_outcome());
+
+        pagerank.calculate_bounded(0.01, u64::MAX);
+        assert_eq!(Some(RunOutcome::Converged), pagerank.last_run_outcome());
+    }
+
+    #[test]
+    fn test_calculate_katz() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("aaa", "ccc");
+        pagerank.calculate_katz(0.5, 1.0, 1e-12).unwrap();
+
+        // a dag, so katz just adds up the attenuated paths
+        assert_eq!(Some(1.0), pagerank.get_score("aaa"));
+        assert_eq!(Some(1.5), pagerank.get_score("bbb"));
+        assert_eq!(Some(1.0 + 0.5 * 1.0 + 0.5 * 1.5), pagerank.get_score("ccc"));
+
+        // two 2-cycles sharing bbb have radius sqrt(2)
+        let mut cyclic = Pagerank::<&str>::new();
+        cyclic.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("bbb", "ccc").add_edge("ccc", "bbb");
+        cyclic.calculate_katz(0.6, 1.0, 1e-9).unwrap();
+        assert_eq!(Some(RunOutcome::Converged), cyclic.last_run_outcome());
+        let score = cyclic.get_score("bbb");
+        assert!(cyclic.calculate_katz(0.75, 1.0, 1e-9).is_err());
+        assert!(cyclic.calculate_katz(f64::NAN, 1.0, 1e-9).is_err());
+        assert_eq!(score, cyclic.get_score("bbb"));
+
+        // doubling every weight halves the largest alpha that converges
+        let mut weighted = Pagerank::<&str>::new();
+        weighted
+            .add_weighted_edge("aaa", "bbb", 2.0)
+            .add_weighted_edge("bbb", "aaa", 2.0)
+            .add_weighted_edge("bbb", "ccc", 2.0)
+            .add_weighted_edge("ccc", "bbb", 2.0);
+        assert!(weighted.calculate_katz(0.6, 1.0, 1e-9).is_err());
+        weighted.calculate_katz(0.3, 1.0, 1e-9).unwrap();
+        assert_eq!(Some(RunOutcome::Converged), weighted.last_run_outcome());
+    }
+
+    #[test]
+    fn test_assert_deterministic() {
+        let edges = (0..50usize)
+            .flat_map(|i| [(i, (i * 7 + 3) % 50), (i, (i * 13 + 1) % 50), ((i * 3) % 50, i)])
+            .collect::<Vec<(usize, usize)>>();
+        Pagerank::assert_deterministic(&edges, 1e-12);
+        Pagerank::<usize>::assert_deterministic(&[], 1e-12);
+    }
+
+    #[test]
+    fn test_from_edge_fn() {
+        // the rows of a 3x3 grid, every cell linking to its right neighbour
+        let mut cell = 0;
+        let pagerank = Pagerank::<(usize, usize)>::from_edge_fn(|| {
+            while cell < 9 {
+                let (row, col) = (cell / 3, cell % 3);
+                cell += 1;
+                if col < 2 {
+                    return Some(((row, col), (row, col + 1)));
+                }
+            }
+            None
+        });
+        assert_eq!(9, pagerank.len());
+        assert_eq!(6, pagerank.len_node());
+        assert_eq!(Some(1), pagerank.get_outgoing_edges((0, 0)));
+    }
+
+    #[test]
+    fn test_ordinal_ranks() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "ddd").add_edge("eee", "ddd");
+        pagerank.calculate();
+
+        let ranks = pagerank.ordinal_ranks();
+        assert_eq!(1, ranks[&"ddd"]);
+        assert_eq!(2, ranks[&"bbb"]);
+        assert_eq!(3, ranks[&"aaa"]);
+        assert_eq!(3, ranks[&"ccc"]);
+        assert_eq!(3, ranks[&"eee"]);
+    }
+
+    #[test]
+    fn test_condensation_pagerank() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "aaa")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "ddd")
+            .add_edge("ddd", "eee")
+            .add_edge("eee", "ccc")
+            .add_edge("aaa", "ddd")
+            .add_edge("fff", "fff");
+
+        let ranked = pagerank.condensation_pagerank(1e-9);
+        assert_eq!(3, ranked.len());
+        // {aaa, bbb} -> {ccc, ddd, eee} collapses to a single edge
+        assert_eq!(vec![&"ccc", &"ddd", &"eee"], ranked[0].0);
+        assert!((ranked[0].1 - (0.15 + 0.85 * 0.15)).abs() < 1e-9);
+        assert_eq!(vec![&"aaa", &"bbb"], ranked[1].0);
+        assert_eq!(vec![&"fff"], ranked[2].0);
+    }
+
+    #[test]
+    fn test_node_damping() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "hub").add_edge("bbb", "hub").add_edge("hub", "aaa");
+        assert!(pagerank.set_node_damping("hub", 1.0).is_err());
+        pagerank.set_node_damping("hub", 0.5).unwrap();
         pagerank.calculate_step();
+
+        // hub mixes 0.5 teleport with 0.5 of what aaa and bbb send it
+        assert!((pagerank.get_score("hub").unwrap() - (0.5 + 0.5 * 0.3)).abs() < 1e-9);
+        assert!((pagerank.get_score("aaa").unwrap() - (0.15 + 0.85 * 0.15)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_leaked_mass() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+        pagerank.calculate_with_convergence(1e-9);
+        assert!(pagerank.leaked_mass().abs() < 1e-6);
+
+        // ccc is dangling so its whole score leaks every step
+        pagerank.add_edge("aaa", "ccc");
+        pagerank.calculate_with_convergence(1e-9);
+        assert!(pagerank.leaked_mass() > 0.5);
+
+        pagerank.set_dangling_handling(DanglingHandling::Teleport);
+        pagerank.calculate_with_convergence(1e-9);
+        assert!(pagerank.leaked_mass().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_over() {
+        let mut left = Pagerank::<&str>::new();
+        left.add_edge("aaa", "bbb").add_edge("bbb", "ccc");
+        let mut middle = Pagerank::<&str>::new();
+        middle.add_edge("ccc", "aaa").add_edge("bbb", "ddd");
+        let mut right = Pagerank::<&str>::new();
+        right.add_edge("ddd", "aaa");
+
+        let mut merged = Pagerank::<&str>::new();
+        merged
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "aaa")
+            .add_edge("bbb", "ddd")
+            .add_edge("ddd", "aaa");
+        merged.calculate_with_convergence(1e-9);
+
+        left.calculate_over([&middle, &right], 1e-9);
+        assert_eq!(4, left.len());
+        assert_eq!(2, left.len_node());
+        for node in ["aaa", "bbb", "ccc", "ddd"] {
+            assert!((merged.get_score(node).unwrap() - left.get_score(node).unwrap()).abs() < 1e-6);
+        }
+
+        for pagerank in [&mut left, &mut merged] {
+            pagerank.set_laziness(0.7).unwrap();
+            pagerank.set_absorbing(vec!["ccc"]);
+            pagerank.set_node_damping("ddd", 0.5).unwrap();
+        }
+        merged.calculate_with_convergence(1e-12);
+        left.calculate_over([&middle, &right], 1e-12);
+        for node in ["aaa", "bbb", "ccc", "ddd"] {
+            assert!((merged.get_score(node).unwrap() - left.get_score(node).unwrap()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_edges_with_scores() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb");
+        pagerank.calculate();
+        let score = |node| pagerank.get_score(node).unwrap();
+
         assert_eq!(
-            vec!["aaa", "bbb", "ccc", "ddd", "eee"],
-            pagerank.nodes()
-                .iter()
-                .map(|(node, _)| **node)
-                .collect::<Vec<&str>>()
+            vec![
+                (&"aaa", score("aaa"), &"bbb", score("bbb")),
+                (&"ccc", score("ccc"), &"bbb", score("bbb")),
+            ],
+            pagerank.edges_with_scores().collect::<Vec<_>>()
         );
     }
 
     #[test]
-    fn test_full_run() {
+    fn test_edge_sensitivity() {
         let mut pagerank = Pagerank::<&str>::new();
-        pagerank.add_edge("aaa", "bbb");
-        pagerank.add_edge("bbb", "aaa");
-        pagerank.add_edge("ddd", "aaa");
-        pagerank.add_edge("eee", "ddd");
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+        pagerank.set_snapshot_iterations(vec![1]);
+        pagerank.set_track_stabilization(true);
+        let before = ranking(&pagerank);
 
-        assert_eq!(16, pagerank.calculate());
+        // adding ddd -> ccc
+        let delta = pagerank
+            .edge_sensitivity("ddd", "ccc", 1e-9)
+            .into_iter()
+            .map(|(node, delta)| (*node, delta))
+            .collect::<HashMap<&str, f64>>();
+        assert!(delta["ccc"] > 0f64);
+        assert!(delta["ddd"].abs() < 1e-9);
+        assert_eq!(None, pagerank.snapshot(1));
+        assert_eq!(None, pagerank.stabilized_at(&"aaa"));
+        assert_eq!(before, ranking(&pagerank));
+        assert_eq!(Some(1), pagerank.get_outgoing_edges("ddd"));
+
+        // removing ddd -> aaa
+        let delta = pagerank
+            .edge_sensitivity("ddd", "aaa", 1e-9)
+            .into_iter()
+            .map(|(node, delta)| (*node, delta))
+            .collect::<HashMap<&str, f64>>();
+        assert!(delta["aaa"] < 0f64);
+        assert_eq!(Some(2), pagerank.get_incoming_edges("aaa"));
+        assert_eq!(4, pagerank.len_node());
+
+        // zzz only exists while ddd -> zzz is being tried out
+        let delta = pagerank
+            .edge_sensitivity("ddd", "zzz", 1e-9)
+            .into_iter()
+            .map(|(node, delta)| (*node, delta))
+            .collect::<HashMap<&str, f64>>();
+        assert_eq!(4, delta.len());
+        assert!(delta["aaa"] < 0f64);
+        assert_eq!(4, pagerank.len());
+
+        // under a full node cap zzz pushes bbb out of the copy
+        pagerank.set_eviction_policy(EvictionPolicy::LowestDegree, 4).unwrap();
+        let delta = pagerank
+            .edge_sensitivity("ddd", "zzz", 1e-9)
+            .into_iter()
+            .map(|(node, delta)| (*node, delta))
+            .collect::<HashMap<&str, f64>>();
+        assert_eq!(4, pagerank.len());
+        pagerank.calculate_with_convergence(1e-9);
+        assert!((pagerank.get_score("bbb").unwrap() + delta["bbb"]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_degree_only() {
+        let mut pagerank = Pagerank::<&str>::new_degree_only();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb").add_edge("bbb", "aaa");
+
+        assert_eq!(Some(2), pagerank.get_incoming_edges("bbb"));
+        assert_eq!(Some(1), pagerank.get_outgoing_edges("bbb"));
+        assert_eq!(3, pagerank.len_node());
+        assert_eq!(Some(0f64), pagerank.get_score("aaa"));
+
+        let mut eager = Pagerank::<&str>::new();
+        eager.add_edge("aaa", "bbb").add_edge("ccc", "bbb").add_edge("bbb", "aaa");
+        assert_eq!(eager.calculate(), pagerank.calculate());
+        assert_eq!(eager.nodes(), pagerank.nodes());
+    }
+
+    #[test]
+    fn test_top_node() {
+        let mut pagerank = Pagerank::<&str>::new();
+        assert_eq!(None, pagerank.top_node());
+
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "ddd").add_edge("eee", "ddd");
+        pagerank.calculate();
+        assert_eq!(pagerank.nodes().first().copied(), pagerank.top_node());
+
+        // ddd and bbb tie exactly and ddd was inserted first, a wide epsilon
+        // ties everything so ccc wins
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("ccc", "ddd").add_edge("aaa", "bbb");
+        pagerank.calculate();
+        assert_eq!(&"ddd", pagerank.top_node().unwrap().0);
+        pagerank.set_sort_epsilon(1.0).unwrap();
+        assert_eq!(&"ccc", pagerank.top_node().unwrap().0);
+    }
+
+    #[test]
+    fn test_feedback_arc_set() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("aaa", "ccc");
+        assert!(pagerank.feedback_arc_set().is_empty());
+
+        pagerank
+            .add_edge("ccc", "aaa")
+            .add_edge("ccc", "ddd")
+            .add_edge("ddd", "ddd")
+            .add_edge("ddd", "eee")
+            .add_edge("eee", "ddd");
+        let cut = pagerank.feedback_arc_set();
+        assert_eq!(3, cut.len());
+        assert!(cut.contains(&(&"ddd", &"ddd")));
+
+        // what's left has to be acyclic
+        let mut rest = Pagerank::<&str>::new();
+        pagerank.edges_by_weight().into_iter().for_each(|(source, target, _)| {
+            if !cut.contains(&(source, target)) {
+                rest.add_edge(*source, *target);
+            }
+        });
+        assert!(rest.feedback_arc_set().is_empty());
+    }
+
+    #[test]
+    fn test_calculate_windowed() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_timed_edge("aaa", "bbb", 10)
+            .add_timed_edge("ccc", "bbb", 20)
+            .add_timed_edge("bbb", "aaa", 30)
+            .add_edge("ccc", "aaa");
+        pagerank.calculate_windowed(15, 30, 0.0001);
+
+        // only ccc -> bbb and bbb -> aaa count
+        let mut windowed = Pagerank::<&str>::new();
+        windowed.add_edge("ccc", "bbb").add_edge("bbb", "aaa");
+        windowed.calculate_with_convergence(0.0001);
+        for node in ["aaa", "bbb", "ccc"] {
+            assert!((pagerank.get_score(node).unwrap() - windowed.get_score(node).unwrap()).abs() < 1e-6);
+        }
+
+        // nothing in the window, everyone sits on the baseline
+        pagerank.calculate_windowed(100, 200, 0.0001);
+        assert_eq!(Some(1f64 - pagerank.damping()), pagerank.get_score("bbb"));
+    }
+
+    #[test]
+    fn test_neighbor_rank_sum() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "ccc")
+            .add_edge("ddd", "aaa");
+        pagerank.calculate();
+        let score = |node| pagerank.get_score(node).unwrap();
+
+        assert_eq!(Some(score("bbb") + score("ccc")), pagerank.neighbor_rank_sum("aaa", Direction::Outgoing));
+        assert_eq!(Some(score("ddd")), pagerank.neighbor_rank_sum("aaa", Direction::Incoming));
+        assert_eq!(Some(0f64), pagerank.neighbor_rank_sum("bbb", Direction::Outgoing));
+        assert_eq!(None, pagerank.neighbor_rank_sum("zzz", Direction::Incoming));
+    }
+
+    #[test]
+    fn test_ranked_groups() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("ccc", "aaa").add_edge("ddd", "bbb");
+        pagerank.calculate();
+
+        let groups = pagerank.ranked_groups(0.001);
+        let names = groups
+            .iter()
+            .map(|group| group.iter().map(|(node, _)| **node).collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(vec![vec!["aaa", "bbb"], vec!["ccc", "ddd"]], names);
+        assert_eq!(4, pagerank.ranked_groups(10f64)[0].len());
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+        pagerank.set_snapshot_iterations(vec![1, 3, 10_000]);
+        pagerank.calculate_with_convergence(1e-9);
+
+        let mut fresh = Pagerank::<&str>::new();
+        fresh.add_edge("aaa", "bbb").add_edge("bbb", "ccc").add_edge("ccc", "aaa").add_edge("ddd", "aaa");
+        fresh.calculate_step();
+        assert_eq!(
+            Some(vec![
+                (&"aaa", fresh.get_score("aaa").unwrap()),
+                (&"bbb", fresh.get_score("bbb").unwrap()),
+                (&"ccc", fresh.get_score("ccc").unwrap()),
+                (&"ddd", fresh.get_score("ddd").unwrap()),
+            ]),
+            pagerank.snapshot(1)
+        );
+        assert!(pagerank.snapshot(3).is_some());
+        assert_eq!(None, pagerank.snapshot(2));
+        assert_eq!(None, pagerank.snapshot(10_000));
+    }
+
+    #[test]
+    fn test_max_incoming_per_node() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "hub")
+            .add_edge("bbb", "hub")
+            .add_edge("ccc", "hub")
+            .add_edge("ddd", "hub");
+        pagerank.set_max_incoming_per_node(2);
+        pagerank.calculate_step();
+
+        assert_eq!(Some(0.15 + 0.85 * 0.3), pagerank.get_score("hub"));
+        assert_eq!(2, pagerank.score_breakdown("hub").unwrap().len());
+        assert_eq!(Some(4), pagerank.get_incoming_edges("hub"));
+    }
+
+    #[test]
+    fn test_stabilized_at() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "aaa")
+            .add_edge("ccc", "bbb")
+            .add_edge("ddd", "aaa");
+        pagerank.calculate_with_convergence(1e-9);
+        assert_eq!(None, pagerank.stabilized_at(&"aaa"));
+
+        pagerank.set_track_stabilization(true);
+        pagerank.reset_scores();
+        pagerank.calculate_with_convergence(1e-9);
+
+        // ddd has no incoming edges so it never moves off the baseline
+        assert_eq!(Some(1), pagerank.stabilized_at(&"ddd"));
+        assert!(pagerank.stabilized_at(&"aaa").unwrap() > 1);
+        assert_eq!(None, pagerank.stabilized_at(&"zzz"));
+    }
+
+    #[test]
+    fn test_map_scores() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("ccc", "bbb");
+        pagerank.calculate();
+        let before = ranking(&pagerank);
+
+        pagerank.map_scores(|score| (1f64 + score).ln()).unwrap();
+
+        for (node, score) in before.iter() {
+            assert_eq!(Some((1f64 + score).ln()), pagerank.get_score(node));
+        }
+
+        // NaN would break the ranking, so none of the scores move
+        assert!(pagerank.map_scores(|score| (score - 0.5).ln()).is_err());
+        for (node, score) in before {
+            assert_eq!(Some((1f64 + score).ln()), pagerank.get_score(node));
+        }
+    }
+
+    #[test]
+    fn test_reachable_count() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "ccc")
+            .add_edge("ccc", "bbb")
+            .add_edge("ddd", "aaa");
+
+        assert_eq!(Some(2), pagerank.reachable_count("aaa"));
+        assert_eq!(Some(3), pagerank.reachable_count("ddd"));
+        // the bbb/ccc cycle comes back around
+        assert_eq!(Some(2), pagerank.reachable_count("bbb"));
+        assert_eq!(None, pagerank.reachable_count("zzz"));
+    }
+
+    #[test]
+    fn test_into_nodes() {
+        let mut pagerank = Pagerank::<String>::new();
+        pagerank.add_edge("aaa".to_string(), "bbb".to_string());
+        pagerank.add_edge("ccc".to_string(), "bbb".to_string());
+        pagerank.calculate();
+
+        let expected = pagerank
+            .nodes()
+            .into_iter()
+            .map(|(node, score)| (node.clone(), score))
+            .collect::<Vec<(String, f64)>>();
+        let owned = pagerank.into_nodes();
+
+        assert_eq!(expected, owned);
+        assert_eq!("bbb", owned[0].0);
+    }
+
+    #[test]
+    fn test_edges_by_weight() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_weighted_edge("aaa", "bbb", 0.5)
+            .add_edge("bbb", "ccc")
+            .add_weighted_edge("ccc", "aaa", 4.0)
+            .add_typed_edge("aaa", "ccc", "cites");
+        pagerank.set_relation_weight("cites", 2.0).unwrap();
 
+        assert_eq!(
+            vec![(&"ccc", &"aaa", 4.0), (&"aaa", &"ccc", 2.0), (&"bbb", &"ccc", 1.0), (&"aaa", &"bbb", 0.5)],
+            pagerank.edges_by_weight()
+        );
+
+        // aaa splits 0.5 : 2.0 between bbb and ccc
+        let breakdown = pagerank.score_breakdown("bbb").unwrap();
+        assert!((breakdown[0].1 - 0.85 * 0.15 * 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_typed_edges() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_typed_edge("aaa", "bbb", "cites")
+            .add_typed_edge("aaa", "ccc", "mentions");
+        assert!(pagerank.set_relation_weight("cites", -1.0).is_err());
+
+        // unweighted relations split evenly
+        pagerank.calculate_step();
+        assert_eq!(pagerank.get_score("bbb"), pagerank.get_score("ccc"));
+
+        pagerank.set_relation_weight("cites", 3.0).unwrap();
+        pagerank.calculate_step();
+        // 0.15 + 0.85 * 0.15 * 3 / 4
+        assert!((pagerank.get_score("bbb").unwrap() - 0.245625).abs() < 1e-9);
+        assert!((pagerank.get_score("ccc").unwrap() - 0.181875).abs() < 1e-9);
+        assert_eq!(2, pagerank.len_node());
+    }
+
+    #[test]
+    fn test_calculate_until_rank_stable() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "bbb")
+            .add_edge("bbb", "aaa")
+            .add_edge("ddd", "aaa")
+            .add_edge("eee", "ddd");
+
+        let iterations = pagerank.calculate_until_rank_stable(0.99);
+        assert_eq!(Some(true), pagerank.last_run_converged());
+        assert!(iterations < 16); // what calculate needs on this graph
         assert_eq!(
             vec!["aaa", "bbb", "ddd", "eee"],
             pagerank.nodes()
@@ -329,6 +5752,141 @@ mod tests {
                 .collect::<Vec<&str>>()
         );
     }
+
+    #[test]
+    fn test_node_weight() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "ccc").add_edge("bbb", "ccc");
+        assert!(pagerank.set_node_weight("aaa", f64::NAN).is_err());
+
+        pagerank.set_node_weight("aaa", 2.0).unwrap();
+        pagerank.calculate_with_convergence(0.0001);
+
+        // teleport shares are 1.5, 0.75 and 0.75
+        assert!((pagerank.get_score("aaa").unwrap() - 0.225).abs() < 1e-9);
+        assert!((pagerank.get_score("bbb").unwrap() - 0.1125).abs() < 1e-9);
+        assert!((pagerank.get_score("ccc").unwrap() - 0.399375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dangling_sink() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("hub", "aaa")
+            .add_edge("aaa", "bbb")
+            .add_edge("aaa", "ccc");
+        pagerank.calculate_step();
+        let leaked = pagerank.get_score("hub").unwrap();
+
+        pagerank.reset_scores();
+        pagerank.set_dangling_sink(vec!["hub"]);
+        pagerank.calculate_step();
+        // bbb and ccc are dangling and hand their 0.15 each over
+        assert!((pagerank.get_score("hub").unwrap() - (leaked + 0.85 * 0.3)).abs() < 1e-9);
+
+        pagerank.set_dangling_sink(vec![]);
+        pagerank.reset_scores();
+        pagerank.calculate_step();
+        assert_eq!(Some(leaked), pagerank.get_score("hub"));
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank
+            .add_edge("aaa", "ccc")
+            .add_edge("bbb", "ccc")
+            .add_edge("bbb", "aaa")
+            .add_edge("ccc", "bbb");
+        pagerank.calculate_with_convergence(1e-9);
+
+        let breakdown = pagerank.score_breakdown("ccc").unwrap();
+        assert_eq!(vec![&"aaa", &"bbb"], breakdown.iter().map(|(node, _)| *node).collect::<Vec<&&str>>());
+
+        let teleport = 1f64 - pagerank.damping();
+        let total = teleport + breakdown.iter().map(|(_, share)| share).sum::<f64>();
+        assert!((total - pagerank.get_score("ccc").unwrap()).abs() < 1e-6);
+        assert_eq!(None, pagerank.score_breakdown("zzz"));
+
+        pagerank.set_node_damping("ccc", 0.5).unwrap();
+        pagerank.calculate_with_convergence(1e-9);
+        let shares = pagerank.score_breakdown("ccc").unwrap().iter().map(|(_, share)| share).sum::<f64>();
+        assert!((0.5 + shares - pagerank.get_score("ccc").unwrap()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_named_scores() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("ccc", "aaa");
+
+        pagerank.calculate_named("standard", 0.0001);
+        pagerank.set_personalization_fn(|node| if *node == "bbb" { 1f64 } else { 0f64 }).unwrap();
+        pagerank.calculate_named("personal", 0.0001);
+
+        // the main scores were never touched
+        assert_eq!(Some(1f64 - pagerank.damping()), pagerank.get_score("aaa"));
+        assert!(pagerank.get_named_score("personal", &"bbb") > pagerank.get_named_score("standard", &"bbb"));
+        assert!(pagerank.get_named_score("standard", &"ccc").is_some());
+        assert_eq!(None, pagerank.get_named_score("nope", &"aaa"));
+        assert_eq!(None, pagerank.get_named_score("standard", &"zzz"));
+    }
+
+    #[test]
+    fn test_calculate_per_component() {
+        let mut pagerank = Pagerank::<&str>::new();
+        // big star and a tiny pair
+        pagerank
+            .add_edge("s1", "hub")
+            .add_edge("s2", "hub")
+            .add_edge("s3", "hub")
+            .add_edge("hub", "s1")
+            .add_edge("ppp", "qqq")
+            .add_edge("qqq", "ppp");
+
+        pagerank.calculate_per_component(0.0001);
+        assert_eq!(Some(true), pagerank.last_run_converged());
+
+        assert_eq!(Some(0), pagerank.component_of(&"hub"));
+        assert_eq!(pagerank.component_of(&"s1"), pagerank.component_of(&"s3"));
+        assert_eq!(Some(1), pagerank.component_of(&"ppp"));
+        assert_eq!(None, pagerank.component_of(&"zzz"));
+
+        // every component is its own distribution
+        let star = ["s1", "s2", "s3", "hub"].iter().map(|n| pagerank.get_score(*n).unwrap()).sum::<f64>();
+        assert!((star - 1f64).abs() < 1e-9);
+        assert!((pagerank.get_score("ppp").unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_per_component_isolated_node() {
+        let mut pagerank = Pagerank::<&str>::new();
+        pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa");
+        pagerank.register_nodes(vec!["ccc"]);
+
+        assert!(pagerank.calculate_per_component(0.0001) < 100);
+        assert_eq!(Some(RunOutcome::Converged), pagerank.last_run_outcome());
+        assert!((pagerank.get_score("ccc").unwrap() - 1f64).abs() < 1e-9);
+        assert!((pagerank.get_score("aaa").unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_run_fixed() {
+        let mut fixed = Pagerank::<&str>::new();
+        let mut stepped = Pagerank::<&str>::new();
+        for pagerank in [&mut fixed, &mut stepped] {
+            pagerank.add_edge("aaa", "bbb").add_edge("bbb", "aaa").add_edge("ccc", "aaa");
+        }
+
+        fixed.run_fixed(7);
+        for _ in 0..7 {
+            stepped.calculate_step();
+        }
+
+        assert!(fixed.approx_eq(&stepped, 0.0));
+        assert_eq!(None, fixed.last_residual());
+        assert_eq!(None, fixed.last_run_outcome());
+    }
+
     #[test]
     /// https://en.wikipedia.org/wiki/PageRank#/media/File:PageRanks-Example.svg
     fn test_pagerank_example() {
@@ -354,8 +5912,9 @@ mod tests {
 
         edges
             .iter()
-            .map(|(l1, l2)| pr.add_edge(*l1, *l2))
-            .for_each(drop);
+            .for_each(|(l1, l2)| {
+                pr.add_edge(*l1, *l2);
+            });
 
         pr.calculate();
 